@@ -0,0 +1,393 @@
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::*,
+        texture::{
+            CompressedImageFormats,
+            ImageSampler,
+            ImageType,
+        },
+    },
+    tasks::ComputeTaskPool,
+    utils::HashMap,
+};
+use cg_scanline::raster::{
+    self,
+    Framebuffer,
+    PixelRect,
+    Primitive,
+    Rasterizer,
+    RenderSettings,
+    Shape,
+    Texture,
+    Vertex,
+};
+
+use crate::constants::{
+    HEIGHT,
+    TILE_SIZE,
+    WIDTH,
+};
+
+pub struct CanvasPlugin;
+
+impl Plugin for CanvasPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_canvas);
+    }
+}
+
+// --------------------
+// tela única em que todos os triângulos são pintados. o framebuffer
+// guarda o resultado da rasterização e é copiado para a imagem exibida
+// pelo sprite. painted guarda a região pintada por cada triângulo,
+// usada para saber o que repintar quando ele muda ou é removido.
+// settings são as opções de pintura da cena, usadas também na
+// exportação. render_time é o tempo gasto na última repintura.
+// --------------------
+
+#[derive(Resource)]
+pub struct Canvas {
+    pub framebuffer: Framebuffer,
+    pub image: Handle<Image>,
+    pub painted: HashMap<Entity, PixelRect>,
+    pub rasterizer: &'static dyn Rasterizer,
+    pub settings: RenderSettings,
+    pub render_time: Duration,
+}
+
+impl Canvas {
+    // --------------------
+    // copia a região alterada do framebuffer para a imagem existente,
+    // sem criar um novo asset nem um novo sprite
+    // --------------------
+
+    pub fn upload(&self, region: &PixelRect, images: &mut Assets<Image>) {
+        if let Some(image) = images.get_mut(&self.image) {
+            self.framebuffer.copy_region_to(region, &mut image.data);
+        }
+    }
+}
+
+
+fn image_from_framebuffer(framebuffer: &Framebuffer) -> Image {
+    let mut image = Image::new(
+        Extent3d {
+            width: framebuffer.width as u32,
+            height: framebuffer.height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        framebuffer.pixels.clone(),
+        TextureFormat::Rgba8Unorm,
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::COPY_DST | 
+        TextureUsages::STORAGE_BINDING | 
+        TextureUsages::TEXTURE_BINDING;
+    image
+}
+
+
+fn setup_canvas(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let framebuffer = Framebuffer::new(WIDTH as usize, HEIGHT as usize);
+
+    let image = images.add(image_from_framebuffer(&framebuffer));
+
+    commands.spawn(SpriteBundle {
+        sprite: Sprite {
+            custom_size: Some(Vec2::new(WIDTH, HEIGHT)),
+            ..default()
+        },
+        texture: image.clone(),
+        transform: Transform::from_translation(Vec3::new(
+            WIDTH / 2.0,
+            HEIGHT / 2.0,
+            0.0,
+        )),
+        ..default()
+    });
+
+    commands.insert_resource(Canvas {
+        framebuffer,
+        image,
+        painted: HashMap::new(),
+        rasterizer: raster::rasterizers()[0],
+        settings: RenderSettings::default(),
+        render_time: Duration::ZERO,
+    });
+}
+
+
+// --------------------
+// junta retângulos que se sobrepõem, para que nenhum pixel
+// seja repintado mais de uma vez na mesma atualização
+// --------------------
+
+pub fn merge_rects(mut rects: Vec<PixelRect>) -> Vec<PixelRect> {
+    let mut merged: Vec<PixelRect> = Vec::new();
+
+    while let Some(mut rect) = rects.pop() {
+        let mut i = 0;
+        while i < merged.len() {
+            if merged[i].intersects(&rect) {
+                rect = rect.union(&merged.swap_remove(i));
+                i = 0;
+            } else {
+                i += 1;
+            }
+        }
+        merged.push(rect);
+    }
+
+    merged
+}
+
+
+// --------------------
+// pinta os triângulos e polígonos (já em ordem) dentro de region, dividindo a
+// região em ladrilhos que são rasterizados em paralelo no
+// ComputeTaskPool. o resultado é idêntico ao da pintura serial.
+// --------------------
+
+pub fn render_parallel<P: Primitive>(
+    rasterizer: &dyn Rasterizer,
+    settings: &RenderSettings,
+    triangles: &[P],
+    framebuffer: &mut Framebuffer,
+    region: &PixelRect,
+) {
+    raster::render_tiled(rasterizer, settings, triangles, framebuffer, region, TILE_SIZE, |tiles, render| {
+        ComputeTaskPool::get().scope(|scope| {
+            for tile in tiles.iter_mut() {
+                scope.spawn(async move {
+                    render(tile);
+                });
+            }
+        });
+    });
+}
+
+
+// --------------------
+// carrega um arquivo PNG do disco como textura RGBA8
+// --------------------
+
+pub fn load_texture(path: &str) -> Result<Texture, String> {
+    let bytes = std::fs::read(path).map_err(|error| error.to_string())?;
+
+    let image = Image::from_buffer(
+        &bytes,
+        ImageType::Extension("png"),
+        CompressedImageFormats::NONE,
+        true,
+        ImageSampler::Default,
+    )
+    .map_err(|error| error.to_string())?;
+
+    let rgba = image
+        .try_into_dynamic()
+        .map_err(|error| error.to_string())?
+        .to_rgba8();
+
+    Ok(Texture {
+        name: path.to_string(),
+        width: rgba.width() as usize,
+        height: rgba.height() as usize,
+        pixels: rgba.into_raw(),
+    })
+}
+
+
+// --------------------
+// pinta a cena com a resolução da tela multiplicada por scale. as
+// posições dos vértices e da luz são multiplicadas junto, para que a
+// iluminação aponte na mesma direção que na tela.
+// --------------------
+
+pub fn render_scaled(
+    rasterizer: &dyn Rasterizer,
+    settings: &RenderSettings,
+    shapes: &[Shape],
+    scale: u32,
+) -> Framebuffer {
+    let scale_factor = scale as f32;
+
+    let shapes: Vec<Shape> = shapes
+        .iter()
+        .cloned()
+        .map(|mut shape| {
+            let vertices: Vec<&mut Vertex> = match &mut shape {
+                Shape::Triangle(triangle) => triangle.vertices.iter_mut().collect(),
+                Shape::Polygon(polygon) => polygon.vertices.iter_mut().chain(polygon.holes.iter_mut().flatten()).collect(),
+            };
+            for vertex in vertices {
+                vertex.position[0] *= scale_factor;
+                vertex.position[1] *= scale_factor;
+            }
+            shape
+        })
+        .collect();
+
+    let mut settings = *settings;
+    if let Some(light) = settings.light.as_mut() {
+        for coordinate in light.position.iter_mut() {
+            *coordinate *= scale_factor;
+        }
+    }
+
+    let mut framebuffer = Framebuffer::new(WIDTH as usize * scale as usize, HEIGHT as usize * scale as usize);
+    let region = framebuffer.region;
+    render_parallel(rasterizer, &settings, &shapes, &mut framebuffer, &region);
+    framebuffer
+}
+
+
+// --------------------
+// exporta a cena para um arquivo PNG, com a resolução da tela
+// multiplicada por scale
+// --------------------
+
+pub fn export_png(
+    rasterizer: &dyn Rasterizer,
+    settings: &RenderSettings,
+    shapes: &[Shape],
+    scale: u32,
+    path: &str,
+) -> Result<(), String> {
+    let framebuffer = render_scaled(rasterizer, settings, shapes, scale);
+
+    // a conversão para PNG só aceita o formato sRGB; os bytes são os mesmos
+    let mut image = image_from_framebuffer(&framebuffer);
+    image.texture_descriptor.format = TextureFormat::Rgba8UnormSrgb;
+
+    image
+        .try_into_dynamic()
+        .map_err(|error| error.to_string())?
+        .save(path)
+        .map_err(|error| error.to_string())
+}
+
+
+// --------------------
+// pinta a cena inteira com cada rasterizador, sem ladrilhos, e
+// descreve o tempo gasto e quantos pixels diferem do primeiro
+// --------------------
+
+pub fn compare_rasterizers(settings: &RenderSettings, shapes: &[Shape]) -> Vec<String> {
+    let mut reference: Option<Framebuffer> = None;
+    let mut lines = Vec::new();
+
+    for &rasterizer in raster::rasterizers() {
+        let mut framebuffer = Framebuffer::new(WIDTH as usize, HEIGHT as usize);
+        let region = framebuffer.region;
+
+        let start = Instant::now();
+        for shape in shapes {
+            shape.render_region(rasterizer, &mut framebuffer, &region, settings);
+        }
+        let time = start.elapsed();
+
+        let mut line = format!("{}: {:.2} ms", rasterizer.name(), time.as_secs_f64() * 1000.0);
+
+        if let Some(reference) = &reference {
+            let different = reference.pixels
+                .chunks_exact(4)
+                .zip(framebuffer.pixels.chunks_exact(4))
+                .filter(|(a, b)| a != b)
+                .count();
+            line += &format!(" ({} pixels diferentes)", different);
+        } else {
+            reference = Some(framebuffer);
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bevy::tasks::TaskPool;
+
+    use cg_scanline::raster::{
+        BlendMode,
+        ColorSpace,
+        Gouraud,
+        PointLight,
+        ShadingModel,
+        Triangle,
+    };
+
+    use super::*;
+
+    #[test]
+    fn scaled_export_is_lit_like_the_canvas() {
+        ComputeTaskPool::get_or_init(TaskPool::default);
+
+        let triangle = |positions: [[f32; 2]; 3], shading| {
+            let normals = [[-0.5, -0.3, 1.0], [0.6, -0.2, 1.0], [0.0, 0.7, 1.0]];
+            Shape::Triangle(Triangle {
+                vertices: [0, 1, 2].map(|i| Vertex {
+                    normal: normals[i],
+                    ..Vertex::new(positions[i], [200, 180, 160, 255])
+                }),
+                edges_color: None,
+                blend_mode: BlendMode::Normal,
+                color_space: ColorSpace::Srgb,
+                texture: None,
+                perspective_correct: true,
+                shading,
+                shader: Arc::new(Gouraud),
+            })
+        };
+
+        let shapes = [
+            triangle([[20.0, 20.0], [300.0, 40.0], [60.0, 300.0]], ShadingModel::Gouraud),
+            triangle([[350.0, 30.0], [620.0, 60.0], [400.0, 320.0]], ShadingModel::Phong),
+        ];
+
+        // luz baixa entre os dois triângulos, para que a direção importe
+        let settings = RenderSettings {
+            light: Some(PointLight {
+                position: [330.0, 150.0, 60.0],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let rasterizer = raster::rasterizers()[0];
+        let canvas = render_scaled(rasterizer, &settings, &shapes, 1);
+        let export = render_scaled(rasterizer, &settings, &shapes, 2);
+
+        // cada pixel da tela é comparado com a média dos quatro pixels
+        // correspondentes da exportação, longe das arestas
+        for (x, y) in [(80, 80), (150, 60), (90, 200), (420, 80), (500, 100), (430, 220)] {
+            let expected = canvas.pixel(x, y);
+            let mut average = [0u32; 4];
+            for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                let pixel = export.pixel(2 * x + dx, 2 * y + dy);
+                for channel in 0..4 {
+                    average[channel] += pixel[channel] as u32;
+                }
+            }
+
+            for channel in 0..4 {
+                let difference = (average[channel] as i32 / 4 - expected[channel] as i32).abs();
+                assert!(difference <= 3, "pixel ({}, {}): {:?} na tela, média {:?} na exportação", x, y, expected, average.map(|sum| sum / 4));
+            }
+        }
+    }
+}
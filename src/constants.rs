@@ -1,2 +1,3 @@
 pub const WIDTH: f32 = 1100.0;
-pub const HEIGHT: f32 = 680.0;
\ No newline at end of file
+pub const HEIGHT: f32 = 680.0;
+pub const TILE_SIZE: usize = 64;
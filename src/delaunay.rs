@@ -0,0 +1,138 @@
+use bevy::{
+    prelude::*,
+    window::PrimaryWindow,
+};
+use bevy_egui::EguiContexts;
+
+use cg_scanline::raster;
+
+use crate::{
+    polygons::click_position,
+    shared_vertices::VertexPool,
+    state::{
+        Function,
+        State,
+    },
+    triangles::{
+        spawn_faces,
+        Vertex,
+    },
+};
+
+pub struct DelaunayPlugin;
+
+impl Plugin for DelaunayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, creating);
+    }
+}
+
+
+// --------------------
+// cada clique adiciona à nuvem um ponto com a cor do color picker
+// --------------------
+
+fn creating(
+    mut egui_contexts: EguiContexts,
+    input: Res<Input<MouseButton>>,
+    mut state: ResMut<State>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+) {
+    if let Function::CreateDelaunay = state.function {
+        let window = window_query.single();
+
+        if let Some(position) = click_position(&mut egui_contexts, &input, MouseButton::Left, window) {
+            let color = state.vertex_color_picker;
+            state.point_cloud.push(Vertex::new(position, color));
+            state.spawn_vertex_selectors = true;
+        }
+    }
+}
+
+
+// --------------------
+// spawna um triângulo para cada face da triangulação de Delaunay da
+// nuvem de pontos, com as cores dos pontos
+// --------------------
+
+pub fn triangulate_point_cloud(commands: &mut Commands, state: &mut State, vertex_pool: &mut VertexPool) {
+    let points: Vec<[f32; 2]> = state.point_cloud.iter().map(|vertex| vertex.position).collect();
+    let faces = raster::delaunay(&points);
+
+    if faces.is_empty() {
+        state.points_message = Some(String::from("São necessários três pontos fora de uma mesma reta."));
+        return;
+    }
+
+    let vertices = std::mem::take(&mut state.point_cloud);
+    spawn_faces(commands, state, vertex_pool, &vertices, &faces);
+
+    state.function = Function::None;
+    state.points_message = None;
+    state.spawn_vertex_selectors = true;
+}
+
+
+// --------------------
+// carrega uma nuvem de pontos de um arquivo de texto com um ponto por
+// linha: "x y r g b", ou "x y r g b a". linhas vazias e as começadas
+// por # são ignoradas.
+// --------------------
+
+pub fn load_points(path: &str) -> Result<Vec<Vertex>, String> {
+    let text = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+    parse_points(&text)
+}
+
+fn parse_points(text: &str) -> Result<Vec<Vertex>, String> {
+    let mut points = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let error = || format!("linha {} inválida: \"{}\"", number + 1, line);
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 5 && fields.len() != 6 {
+            return Err(error());
+        }
+
+        // "nan" e "inf" também são aceitos por parse, mas não são pontos
+        let x: f32 = fields[0].parse().ok().filter(|x: &f32| x.is_finite()).ok_or_else(error)?;
+        let y: f32 = fields[1].parse().ok().filter(|y: &f32| y.is_finite()).ok_or_else(error)?;
+        let mut color = [255; 4];
+        for (channel, field) in color.iter_mut().zip(&fields[2..]) {
+            *channel = field.parse().map_err(|_| error())?;
+        }
+
+        points.push(Vertex::new([x, y], color));
+    }
+
+    Ok(points)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_file_is_parsed_line_by_line() {
+        let points = parse_points("# nuvem\n10 20 255 0 0\n\n  30.5 40 0 255 0 128\n").unwrap();
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].position, [10.0, 20.0]);
+        assert_eq!(points[0].color, [255, 0, 0, 255]);
+        assert_eq!(points[1].position, [30.5, 40.0]);
+        assert_eq!(points[1].color, [0, 255, 0, 128]);
+
+        assert!(parse_points("10 20 255 0").is_err());
+        assert!(parse_points("10 20 300 0 0").unwrap_err().contains("linha 1"));
+        assert!(parse_points("10 20 0 0 0\nnan 5 0 0 0").unwrap_err().contains("linha 2"));
+        assert!(parse_points("10 inf 0 0 0").unwrap_err().contains("linha 1"));
+        assert!(parse_points("-infinity 10 0 0 0").is_err());
+    }
+}
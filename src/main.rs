@@ -1,6 +1,11 @@
 mod state;
 mod camera;
+mod canvas;
 mod constants;
+mod delaunay;
+mod polygons;
+mod scene;
+mod shared_vertices;
 mod triangles;
 mod ui;
 
@@ -12,10 +17,14 @@ use bevy_egui::EguiPlugin;
 
 use crate::{
     camera::CameraPlugin,
+    canvas::CanvasPlugin,
     constants::{
         HEIGHT, 
         WIDTH,
     },
+    delaunay::DelaunayPlugin,
+    polygons::PolygonsPlugin,
+    shared_vertices::SharedVerticesPlugin,
     state::StatePlugin,
     triangles::TrianglesPlugin,
     ui::UIPlugin,
@@ -42,9 +51,13 @@ fn main() {
             })
         )
         .add_plugins(CameraPlugin)
+        .add_plugins(CanvasPlugin)
         .add_plugins(EguiPlugin)
         .add_plugins(StatePlugin)
         .add_plugins(UIPlugin)
         .add_plugins(TrianglesPlugin)
+        .add_plugins(PolygonsPlugin)
+        .add_plugins(DelaunayPlugin)
+        .add_plugins(SharedVerticesPlugin)
         .run();
 }
\ No newline at end of file
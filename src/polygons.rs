@@ -0,0 +1,290 @@
+use bevy::{
+    prelude::*,
+    window::PrimaryWindow,
+};
+use bevy_egui::EguiContexts;
+
+use cg_scanline::raster::{
+    self,
+    BlendMode,
+    FillRule,
+};
+
+use crate::{
+    state::{
+        Function,
+        State,
+    },
+    triangles::{
+        Vertex,
+        VertexOrder,
+    },
+};
+
+pub struct PolygonsPlugin;
+
+impl Plugin for PolygonsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, creating)
+            .add_systems(Update, creating_hole)
+            .add_systems(Update, modifying);
+    }
+}
+
+// --------------------
+// polígono com qualquer número de vértices e de furos. divide com os
+// triângulos o contador de índices, que define a ordem de pintura.
+// --------------------
+
+#[derive(Debug, Component)]
+pub struct Polygon {
+    pub vertices: Vec<Vertex>,
+    pub holes: Vec<Vec<Vertex>>,
+    pub redraw: bool,
+    pub index: usize,
+    pub edges_color: Option<[u8; 3]>,
+    pub blend_mode: BlendMode,
+    pub fill_rule: FillRule,
+}
+
+impl Polygon {
+    pub fn to_raster(&self) -> raster::Polygon {
+        raster::Polygon {
+            vertices: self.vertices.clone(),
+            holes: self.holes.clone(),
+            edges_color: self.edges_color,
+            blend_mode: self.blend_mode,
+            fill_rule: self.fill_rule,
+        }
+    }
+
+    // --------------------
+    // os vértices são numerados em sequência: primeiro os do contorno
+    // externo, depois os de cada furo, na ordem em que foram adicionados
+    // --------------------
+
+    pub fn all_vertices(&self) -> impl Iterator<Item = &Vertex> {
+        self.vertices.iter().chain(self.holes.iter().flatten())
+    }
+
+    pub fn vertex_mut(&mut self, index: usize) -> Option<&mut Vertex> {
+        self.vertices.iter_mut().chain(self.holes.iter_mut().flatten()).nth(index)
+    }
+}
+
+
+// --------------------
+// posição do clique na tela (com y para cima), se ele não for sobre a interface
+// --------------------
+
+pub fn click_position(
+    egui_contexts: &mut EguiContexts,
+    input: &Input<MouseButton>,
+    button: MouseButton,
+    window: &Window,
+) -> Option<[f32; 2]> {
+    let ctx = egui_contexts.ctx_mut();
+
+    if !input.just_pressed(button) || ctx.is_using_pointer() || ctx.is_pointer_over_area() {
+        return None;
+    }
+
+    window
+        .cursor_position()
+        .map(|cursor_position| [cursor_position.x, window.height() - cursor_position.y])
+}
+
+// índice do vértice a no máximo 8 pixels do clique
+fn vertex_near<'a>(vertices: impl IntoIterator<Item = &'a Vertex>, click: [f32; 2]) -> Option<usize> {
+    vertices.into_iter().position(|vertex| {
+        (click[0] - vertex.position[0]).abs() < 8.0 && (click[1] - vertex.position[1]).abs() < 8.0
+    })
+}
+
+
+// --------------------
+// spawna o polígono com os pontos de new_polygon, se houver pelo menos três
+// --------------------
+
+pub fn close_polygon(commands: &mut Commands, state: &mut State) {
+    if state.new_polygon.len() < 3 {
+        return;
+    }
+
+    let polygon = Polygon {
+        vertices: std::mem::take(&mut state.new_polygon),
+        holes: Vec::new(),
+        redraw: true,
+        index: state.triangles_count,
+        edges_color: if state.constant_edges {
+            Some(state.edges_color_picker)
+        } else {
+            None
+        },
+        blend_mode: BlendMode::Normal,
+        fill_rule: state.fill_rule,
+    };
+
+    let entity = commands.spawn(polygon).id();
+
+    state.function = Function::ModifyPolygon(entity);
+    state.selected_vertex = None;
+    state.spawn_vertex_selectors = true;
+    state.triangles_count += 1;
+}
+
+
+// --------------------
+// adiciona ao polígono um furo com os pontos de new_polygon, se houver
+// pelo menos três, e volta à edição do polígono
+// --------------------
+
+pub fn close_hole(state: &mut State, entity: Entity, polygon: &mut Polygon) {
+    if state.new_polygon.len() < 3 {
+        return;
+    }
+
+    polygon.holes.push(std::mem::take(&mut state.new_polygon));
+    polygon.redraw = true;
+
+    state.function = Function::ModifyPolygon(entity);
+    state.selected_vertex = None;
+    state.spawn_vertex_selectors = true;
+}
+
+
+fn new_point(state: &State, position: [f32; 2]) -> Vertex {
+    Vertex::new(position, state.vertex_color_picker)
+}
+
+
+fn creating(
+    mut commands: Commands,
+    mut egui_contexts: EguiContexts,
+    input: Res<Input<MouseButton>>,
+    mut state: ResMut<State>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+) {
+    if let Function::CreatePolygon = state.function {
+        let window = window_query.single();
+
+        // --------------------
+        // cada clique adiciona um ponto; um clique sobre o primeiro ponto
+        // fecha o polígono
+        // --------------------
+
+        if let Some(position) = click_position(&mut egui_contexts, &input, MouseButton::Left, window) {
+            if state.new_polygon.len() >= 3 && vertex_near(&state.new_polygon[..1], position).is_some() {
+                close_polygon(&mut commands, &mut state);
+                return;
+            }
+
+            let vertex = new_point(&state, position);
+            state.new_polygon.push(vertex);
+
+            state.spawn_vertex_selectors = true;
+        }
+    }
+}
+
+
+fn creating_hole(
+    mut egui_contexts: EguiContexts,
+    input: Res<Input<MouseButton>>,
+    mut state: ResMut<State>,
+    mut polygons_query: Query<&mut Polygon>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+) {
+    if let Function::CreateHole(entity) = state.function {
+        let window = window_query.single();
+        let Ok(mut polygon) = polygons_query.get_mut(entity) else {
+            return;
+        };
+
+        // --------------------
+        // como na criação do polígono: cada clique adiciona um ponto ao
+        // furo, e um clique sobre o primeiro ponto o fecha
+        // --------------------
+
+        if let Some(position) = click_position(&mut egui_contexts, &input, MouseButton::Left, window) {
+            if state.new_polygon.len() >= 3 && vertex_near(&state.new_polygon[..1], position).is_some() {
+                close_hole(&mut state, entity, &mut polygon);
+                return;
+            }
+
+            let vertex = new_point(&state, position);
+            state.new_polygon.push(vertex);
+
+            state.spawn_vertex_selectors = true;
+        }
+    }
+}
+
+
+fn modifying(
+    mut egui_contexts: EguiContexts,
+    input: Res<Input<MouseButton>>,
+    mut state: ResMut<State>,
+    mut polygons_query: Query<&mut Polygon>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+) {
+    if let Function::ModifyPolygon(entity) = state.function {
+        let window = window_query.single();
+        let Ok(mut polygon) = polygons_query.get_mut(entity) else {
+            return;
+        };
+
+        // --------------------
+        // o botão direito atribui a cor do color picker ao vértice clicado
+        // --------------------
+
+        if let Some(position) = click_position(&mut egui_contexts, &input, MouseButton::Right, window) {
+            if let Some(index) = vertex_near(polygon.all_vertices(), position) {
+                if let Some(vertex) = polygon.vertex_mut(index) {
+                    vertex.color = state.vertex_color_picker;
+                }
+                polygon.redraw = true;
+                state.spawn_vertex_selectors = true;
+            }
+        }
+
+        // --------------------
+        // o primeiro clique com o botão esquerdo seleciona um vértice, e
+        // o segundo o desloca para a posição do clique
+        // --------------------
+
+        if let Some(position) = click_position(&mut egui_contexts, &input, MouseButton::Left, window) {
+            if let Some(VertexOrder::Index(index)) = state.selected_vertex {
+                if let Some(vertex) = polygon.vertex_mut(index) {
+                    vertex.position = position;
+                    polygon.redraw = true;
+                }
+                state.selected_vertex = None;
+                state.spawn_vertex_selectors = true;
+            } else if let Some(index) = vertex_near(polygon.all_vertices(), position) {
+                state.selected_vertex = Some(VertexOrder::Index(index));
+                state.spawn_vertex_selectors = true;
+            }
+        }
+    }
+}
+
+
+// --------------------
+// passa a editar o polígono selecionado, com as opções dele na interface
+// --------------------
+
+pub fn select_polygon(state: &mut State, entity: Entity, polygon: &Polygon) {
+    state.function = Function::ModifyPolygon(entity);
+    state.selected_vertex = None;
+    state.spawn_vertex_selectors = true;
+    state.fill_rule = polygon.fill_rule;
+
+    if let Some(edges_color) = polygon.edges_color {
+        state.edges_color_picker = edges_color;
+        state.constant_edges = true;
+    } else {
+        state.constant_edges = false;
+    }
+}
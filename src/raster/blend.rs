@@ -0,0 +1,112 @@
+// --------------------
+// modos de mistura usados ao compor um triângulo sobre o que já foi
+// pintado. as cores têm alfa não pré-multiplicado. nos modos separáveis
+// (normal, multiplicação e tela) a cor da fonte é primeiro misturada com
+// a do destino pela função do modo e depois composta com o operador
+// "over"; no aditivo as cores pré-multiplicadas são somadas.
+// --------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Additive,
+    Multiply,
+    Screen,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 4] = [
+        BlendMode::Normal,
+        BlendMode::Additive,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BlendMode::Normal => "Normal",
+            BlendMode::Additive => "Aditivo",
+            BlendMode::Multiply => "Multiplicação",
+            BlendMode::Screen => "Tela",
+        }
+    }
+
+    // --------------------
+    // compõe source sobre target (ambos RGBA8) e devolve o resultado
+    // --------------------
+
+    pub fn blend(&self, source: [u8; 4], target: [u8; 4]) -> [u8; 4] {
+        let source_alpha = source[3] as f32 / 255.0;
+        let target_alpha = target[3] as f32 / 255.0;
+
+        if source_alpha == 0.0 {
+            return target;
+        }
+        if *self == BlendMode::Normal && source_alpha == 1.0 {
+            return source;
+        }
+
+        let channel = |i: usize| (source[i] as f32 / 255.0, target[i] as f32 / 255.0);
+
+        let (alpha, premultiplied) = match self {
+            BlendMode::Additive => {
+                let premultiplied = [0, 1, 2].map(|i| {
+                    let (s, t) = channel(i);
+                    (s * source_alpha + t * target_alpha).min(1.0)
+                });
+                ((source_alpha + target_alpha).min(1.0), premultiplied)
+            }
+            _ => {
+                let premultiplied = [0, 1, 2].map(|i| {
+                    let (s, t) = channel(i);
+                    let mixed = match self {
+                        BlendMode::Multiply => s * t,
+                        BlendMode::Screen => s + t - s * t,
+                        _ => s,
+                    };
+                    let s = (1.0 - target_alpha) * s + target_alpha * mixed;
+                    s * source_alpha + t * target_alpha * (1.0 - source_alpha)
+                });
+                (source_alpha + target_alpha * (1.0 - source_alpha), premultiplied)
+            }
+        };
+
+        let to_u8 = |value: f32| (value * 255.0).round().clamp(0.0, 255.0) as u8;
+
+        [
+            to_u8(premultiplied[0] / alpha),
+            to_u8(premultiplied[1] / alpha),
+            to_u8(premultiplied[2] / alpha),
+            to_u8(alpha),
+        ]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modes_over_an_opaque_target() {
+        let target = [200, 100, 0, 255];
+
+        assert_eq!(BlendMode::Normal.blend([10, 20, 30, 255], target), [10, 20, 30, 255]);
+        assert_eq!(BlendMode::Normal.blend([0, 0, 255, 0], target), target);
+        assert_eq!(BlendMode::Normal.blend([0, 0, 0, 51], target), [160, 80, 0, 255]);
+
+        assert_eq!(BlendMode::Additive.blend([100, 200, 255, 255], target), [255, 255, 255, 255]);
+        assert_eq!(BlendMode::Multiply.blend([255, 0, 255, 255], target), [200, 0, 0, 255]);
+        assert_eq!(BlendMode::Screen.blend([0, 255, 255, 255], target), [200, 255, 255, 255]);
+    }
+
+    #[test]
+    fn modes_over_a_transparent_target_keep_the_source() {
+        let source = [40, 80, 120, 128];
+
+        for mode in BlendMode::ALL {
+            assert_eq!(mode.blend(source, [0, 0, 0, 0]), source, "{}", mode.name());
+        }
+    }
+}
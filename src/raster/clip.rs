@@ -0,0 +1,297 @@
+// --------------------
+// vértice usado internamente pelo rasterizador, com os valores a
+// interpolar (varyings) em ponto flutuante para que os pontos criados
+// pelo recorte não percam precisão
+// --------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipVertex {
+    pub position: [f32; 2],
+    pub varyings: Vec<f32>,
+}
+
+impl ClipVertex {
+    fn lerp(&self, other: &ClipVertex, t: f32) -> ClipVertex {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+
+        ClipVertex {
+            position: [
+                mix(self.position[0], other.position[0]),
+                mix(self.position[1], other.position[1]),
+            ],
+            varyings: self
+                .varyings
+                .iter()
+                .zip(&other.varyings)
+                .map(|(&a, &b)| mix(a, b))
+                .collect(),
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Copy)]
+enum Boundary {
+    Left(f32),
+    Right(f32),
+    Bottom(f32),
+    Top(f32),
+}
+
+impl Boundary {
+    fn inside(&self, position: [f32; 2]) -> bool {
+        match *self {
+            Boundary::Left(x) => position[0] >= x,
+            Boundary::Right(x) => position[0] <= x,
+            Boundary::Bottom(y) => position[1] >= y,
+            Boundary::Top(y) => position[1] <= y,
+        }
+    }
+
+    // --------------------
+    // ponto em que a aresta cruza a borda. os extremos são ordenados antes
+    // do cálculo para que a mesma aresta, compartilhada por dois triângulos
+    // e percorrida em sentidos opostos, gere exatamente o mesmo ponto.
+    // --------------------
+
+    fn intersect(&self, a: &ClipVertex, b: &ClipVertex) -> ClipVertex {
+        let (a, b) = if (a.position[0], a.position[1]) <= (b.position[0], b.position[1]) {
+            (a, b)
+        } else {
+            (b, a)
+        };
+
+        let t = match *self {
+            Boundary::Left(x) | Boundary::Right(x) => (x - a.position[0]) / (b.position[0] - a.position[0]),
+            Boundary::Bottom(y) | Boundary::Top(y) => (y - a.position[1]) / (b.position[1] - a.position[1]),
+        };
+
+        let mut vertex = a.lerp(b, t);
+
+        // fixa a coordenada recortada exatamente sobre a borda
+        match *self {
+            Boundary::Left(x) | Boundary::Right(x) => vertex.position[0] = x,
+            Boundary::Bottom(y) | Boundary::Top(y) => vertex.position[1] = y,
+        }
+
+        vertex
+    }
+}
+
+
+// --------------------
+// recorte de Sutherland–Hodgman do polígono contra o retângulo [min, max]
+// --------------------
+
+pub fn clip_polygon(vertices: &[ClipVertex], min: [f32; 2], max: [f32; 2]) -> Vec<ClipVertex> {
+    let boundaries = [
+        Boundary::Left(min[0]),
+        Boundary::Right(max[0]),
+        Boundary::Bottom(min[1]),
+        Boundary::Top(max[1]),
+    ];
+
+    let mut output = vertices.to_vec();
+
+    for boundary in boundaries {
+        if output.is_empty() {
+            break;
+        }
+
+        let input = std::mem::take(&mut output);
+
+        for i in 0..input.len() {
+            let current = &input[i];
+            let previous = &input[(i + input.len() - 1) % input.len()];
+
+            let current_inside = boundary.inside(current.position);
+            let previous_inside = boundary.inside(previous.position);
+
+            if current_inside {
+                if !previous_inside {
+                    output.push(boundary.intersect(previous, current));
+                }
+                output.push(current.clone());
+            } else if previous_inside {
+                output.push(boundary.intersect(previous, current));
+            }
+        }
+    }
+
+    output
+}
+
+
+// --------------------
+// recorte de Liang–Barsky de um segmento contra o retângulo [min, max]
+// --------------------
+
+pub fn clip_segment(p0: [f32; 2], p1: [f32; 2], min: [f32; 2], max: [f32; 2]) -> Option<([f32; 2], [f32; 2])> {
+    let dx = p1[0] - p0[0];
+    let dy = p1[1] - p0[1];
+
+    let mut t0: f32 = 0.0;
+    let mut t1: f32 = 1.0;
+
+    for (p, q) in [
+        (-dx, p0[0] - min[0]),
+        (dx, max[0] - p0[0]),
+        (-dy, p0[1] - min[1]),
+        (dy, max[1] - p0[1]),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    Some((
+        [p0[0] + dx * t0, p0[1] + dy * t0],
+        [p0[0] + dx * t1, p0[1] + dy * t1],
+    ))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raster::{
+        scanline::fill_polygon,
+        FillRule,
+        Framebuffer,
+        RenderSettings,
+    };
+
+    // vértice com a cor RGB nos varyings
+    fn vertex(position: [f32; 2], color: [f32; 3]) -> ClipVertex {
+        ClipVertex {
+            position,
+            varyings: color.to_vec(),
+        }
+    }
+
+    fn assert_same_vertices(clipped: &[ClipVertex], expected: &[ClipVertex]) {
+        assert_eq!(clipped.len(), expected.len(), "{:?}", clipped);
+
+        for expected in expected {
+            let found = clipped.iter().any(|vertex| {
+                vertex.position.iter().zip(&expected.position).all(|(a, b)| (a - b).abs() < 1e-3)
+                    && vertex.varyings.iter().zip(&expected.varyings).all(|(a, b)| (a - b).abs() < 1e-3)
+            });
+            assert!(found, "{:?} não está em {:?}", expected, clipped);
+        }
+    }
+
+    const RED: [f32; 3] = [255.0, 0.0, 0.0];
+    const GREEN: [f32; 3] = [0.0, 255.0, 0.0];
+    const BLUE: [f32; 3] = [0.0, 0.0, 255.0];
+
+    #[test]
+    fn triangle_crossing_each_edge_gets_interpolated_colors_at_the_clip_points() {
+        // um vértice 20 pixels para fora da borda esquerda; as arestas que
+        // saem dele cruzam a borda em um quarto do caminho. o mesmo
+        // triângulo é girado e espelhado para cruzar cada uma das bordas.
+        let size = 100.0;
+        let edges: [fn([f32; 2]) -> [f32; 2]; 4] = [
+            |[x, y]| [x, y],
+            |[x, y]| [100.0 - x, y],
+            |[x, y]| [y, x],
+            |[x, y]| [y, 100.0 - x],
+        ];
+
+        let mix = |a: [f32; 3], b: [f32; 3], t: f32| [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t);
+
+        for place in edges {
+            let triangle = [
+                vertex(place([-20.0, 50.0]), RED),
+                vertex(place([60.0, 10.0]), GREEN),
+                vertex(place([60.0, 90.0]), BLUE),
+            ];
+
+            let clipped = clip_polygon(&triangle, [0.0, 0.0], [size, size]);
+
+            assert_same_vertices(&clipped, &[
+                vertex(place([0.0, 40.0]), mix(RED, GREEN, 0.25)),
+                vertex(place([0.0, 60.0]), mix(RED, BLUE, 0.25)),
+                triangle[1].clone(),
+                triangle[2].clone(),
+            ]);
+        }
+    }
+
+    #[test]
+    fn triangle_outside_is_clipped_away() {
+        for triangle in [
+            // todo à esquerda
+            [[-50.0, 10.0], [-10.0, 10.0], [-30.0, 60.0]],
+            // perto do canto: o retângulo envolvente cruza a tela, o triângulo não
+            [[-30.0, 10.0], [10.0, -30.0], [-30.0, -30.0]],
+            // todo acima
+            [[10.0, 120.0], [90.0, 120.0], [50.0, 180.0]],
+        ] {
+            let triangle = [vertex(triangle[0], RED), vertex(triangle[1], GREEN), vertex(triangle[2], BLUE)];
+            assert!(clip_polygon(&triangle, [0.0, 0.0], [100.0, 100.0]).is_empty());
+        }
+    }
+
+    #[test]
+    fn triangle_inside_is_unchanged() {
+        let triangle = [
+            vertex([0.0, 0.0], RED),
+            vertex([100.0, 30.5], GREEN),
+            vertex([12.25, 99.0], BLUE),
+        ];
+
+        assert_eq!(clip_polygon(&triangle, [0.0, 0.0], [100.0, 100.0]), triangle.to_vec());
+    }
+
+    #[test]
+    fn segment_is_clipped_to_the_rectangle() {
+        assert_eq!(clip_segment([-10.0, 5.0], [30.0, 5.0], [0.0, 0.0], [20.0, 20.0]), Some(([0.0, 5.0], [20.0, 5.0])));
+        assert_eq!(clip_segment([-10.0, -10.0], [30.0, 30.0], [0.0, 0.0], [20.0, 20.0]), Some(([0.0, 0.0], [20.0, 20.0])));
+        assert_eq!(clip_segment([5.0, 5.0], [15.0, 10.0], [0.0, 0.0], [20.0, 20.0]), Some(([5.0, 5.0], [15.0, 10.0])));
+        assert_eq!(clip_segment([-10.0, 30.0], [30.0, 25.0], [0.0, 0.0], [20.0, 20.0]), None);
+    }
+
+    #[test]
+    fn negative_coordinates_are_filled_without_panicking() {
+        let triangle = [
+            vertex([-40.0, -25.0], RED),
+            vertex([30.0, -60.0], GREEN),
+            vertex([-15.0, 35.0], BLUE),
+        ];
+
+        // conta os pixels entregues pela scanline, que devem estar na tela
+        let painted = |contour: Vec<ClipVertex>| {
+            let mut framebuffer = Framebuffer::new(32, 32);
+            let region = framebuffer.region;
+            let mut painted = 0;
+
+            fill_polygon(&[contour], FillRule::EvenOdd, &mut framebuffer, &region, &RenderSettings::default(), &mut |_, x, y, _, _| {
+                assert!(x < 32 && y < 32);
+                painted += 1;
+            });
+
+            painted
+        };
+
+        // sem recorte, a scanline precisa ignorar sozinha o que está fora
+        let unclipped = painted(triangle.to_vec());
+        let clipped = painted(clip_polygon(&triangle, [0.0, 0.0], [32.0, 32.0]));
+
+        assert!(clipped > 0);
+        assert_eq!(clipped, unclipped);
+    }
+}
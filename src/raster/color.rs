@@ -0,0 +1,228 @@
+// --------------------
+// espaço de cor em que as cores dos vértices são interpoladas. as cores
+// dos vértices são convertidas para o espaço escolhido, interpoladas
+// pelo rasterizador e convertidas de volta para sRGB em cada pixel. o
+// alfa é sempre interpolado de forma linear, de 0 a 255.
+//
+// faixas dos valores em cada espaço:
+// - sRGB: r, g, b em [0, 255], como as cores dos vértices
+// - RGB linear: r, g, b em [0, 1], sem a curva de gama do sRGB
+// - HSV: matiz em graus, saturação e valor em [0, 1]
+// - Oklab: L em [0, 1], a e b aproximadamente em [-0.4, 0.4]
+// --------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    LinearRgb,
+    Hsv,
+    Oklab,
+}
+
+impl ColorSpace {
+    pub const ALL: [ColorSpace; 4] = [
+        ColorSpace::Srgb,
+        ColorSpace::LinearRgb,
+        ColorSpace::Hsv,
+        ColorSpace::Oklab,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorSpace::Srgb => "sRGB",
+            ColorSpace::LinearRgb => "RGB linear",
+            ColorSpace::Hsv => "HSV",
+            ColorSpace::Oklab => "Oklab",
+        }
+    }
+
+    pub fn encode(&self, color: [u8; 4]) -> [f32; 4] {
+        let rgb = [color[0], color[1], color[2]].map(|c| c as f32);
+        let value = match self {
+            ColorSpace::Srgb => rgb,
+            ColorSpace::LinearRgb => rgb.map(|c| srgb_to_linear(c / 255.0)),
+            ColorSpace::Hsv => rgb_to_hsv(rgb.map(|c| c / 255.0)),
+            ColorSpace::Oklab => linear_to_oklab(rgb.map(|c| srgb_to_linear(c / 255.0))),
+        };
+
+        [value[0], value[1], value[2], color[3] as f32]
+    }
+
+    // --------------------
+    // converte um valor interpolado de volta para sRGB em [0, 255]
+    // --------------------
+
+    pub fn decode(&self, value: [f32; 4]) -> [f32; 4] {
+        let rgb = [value[0], value[1], value[2]];
+        let rgb = match self {
+            ColorSpace::Srgb => rgb,
+            ColorSpace::LinearRgb => rgb.map(|c| linear_to_srgb(c) * 255.0),
+            ColorSpace::Hsv => hsv_to_rgb(rgb).map(|c| c * 255.0),
+            ColorSpace::Oklab => oklab_to_linear(rgb).map(|c| linear_to_srgb(c) * 255.0),
+        };
+
+        [rgb[0], rgb[1], rgb[2], value[3]]
+    }
+
+    // --------------------
+    // converte as cores dos três vértices. em HSV, as matizes são
+    // ajustadas em múltiplos de 360 graus para que a interpolação siga
+    // o caminho mais curto em volta do círculo de cores.
+    // --------------------
+
+    pub fn encode_vertices(&self, colors: [[u8; 4]; 3]) -> [[f32; 4]; 3] {
+        let mut values = colors.map(|color| self.encode(color));
+
+        if *self == ColorSpace::Hsv {
+            let hue = values[0][0];
+            for value in values.iter_mut().skip(1) {
+                if value[0] - hue > 180.0 {
+                    value[0] -= 360.0;
+                } else if hue - value[0] > 180.0 {
+                    value[0] += 360.0;
+                }
+            }
+        }
+
+        values
+    }
+}
+
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    let c = c.clamp(0.0, 1.0);
+    if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+}
+
+
+fn rgb_to_hsv([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+    [hue, saturation, max]
+}
+
+fn hsv_to_rgb([hue, saturation, value]: [f32; 3]) -> [f32; 3] {
+    let hue = hue.rem_euclid(360.0) / 60.0;
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (hue.rem_euclid(2.0) - 1.0).abs());
+    let m = value - chroma;
+
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    [r + m, g + m, b + m]
+}
+
+
+// --------------------
+// conversões entre RGB linear e Oklab (Björn Ottosson, 2020)
+// --------------------
+
+fn linear_to_oklab([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+fn oklab_to_linear([lightness, a, b]: [f32; 3]) -> [f32; 3] {
+    let l = (lightness + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+    let m = (lightness - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+    let s = (lightness - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+
+    [
+        4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+        -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+        -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+    ]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertex_colors_survive_a_round_trip() {
+        let colors = [
+            [0, 0, 0, 255],
+            [255, 255, 255, 255],
+            [255, 0, 0, 128],
+            [12, 200, 99, 0],
+            [250, 251, 3, 77],
+            [1, 2, 254, 255],
+        ];
+
+        for space in ColorSpace::ALL {
+            for color in colors {
+                let decoded = space.decode(space.encode(color)).map(|c| c.round() as u8);
+                assert_eq!(decoded, color, "{}", space.name());
+            }
+        }
+    }
+
+    #[test]
+    fn midpoint_between_red_and_green() {
+        let midpoint = |space: ColorSpace| {
+            let [red, green, _] = space.encode_vertices([[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 0, 255]]);
+            let value = [0, 1, 2, 3].map(|i| (red[i] + green[i]) / 2.0);
+            space.decode(value).map(|c| c.round() as u8)
+        };
+
+        assert_eq!(midpoint(ColorSpace::Srgb), [128, 128, 0, 255]);
+        assert_eq!(midpoint(ColorSpace::LinearRgb), [188, 188, 0, 255]);
+        assert_eq!(midpoint(ColorSpace::Hsv), [255, 255, 0, 255]);
+        // em Oklab o meio fica mais claro que em sRGB e puxado para o verde
+        assert_eq!(midpoint(ColorSpace::Oklab), [208, 168, 0, 255]);
+    }
+
+    #[test]
+    fn oklab_matches_the_reference_values_and_round_trips() {
+        let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(&b).all(|(a, b)| (a - b).abs() < 1e-4);
+
+        // valores publicados por Ottosson para o branco e o vermelho
+        assert!(close(linear_to_oklab([1.0, 1.0, 1.0]), [1.0, 0.0, 0.0]));
+        assert!(close(linear_to_oklab([1.0, 0.0, 0.0]), [0.627_955, 0.224_863, 0.125_846]));
+
+        for rgb in [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.2, 0.5, 0.9],
+            [0.8, 0.75, 0.01],
+        ] {
+            let back = oklab_to_linear(linear_to_oklab(rgb));
+            assert!(close(back, rgb), "{:?} -> {:?}", rgb, back);
+        }
+    }
+}
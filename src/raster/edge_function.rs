@@ -0,0 +1,146 @@
+use super::{
+    fragment::{
+        shade,
+        vertex_varyings,
+    },
+    line::draw_edges,
+    Framebuffer,
+    PixelRect,
+    Rasterizer,
+    RenderSettings,
+    Triangle,
+};
+
+// --------------------
+// rasterizador por funções de aresta (half-space). percorre o retângulo
+// envolvente do triângulo e testa cada centro de pixel contra as três
+// arestas. as funções de aresta normalizadas pela área são as
+// coordenadas baricêntricas do pixel, usadas para interpolar os valores
+// dos vértices (cor, profundidade, coordenadas de textura).
+// com antisserrilhamento, cada pixel é testado em uma grade de
+// samples x samples pontos e a fração coberta multiplica o alfa.
+// --------------------
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EdgeFunction;
+
+impl Rasterizer for EdgeFunction {
+    fn name(&self) -> &'static str {
+        "Funções de aresta"
+    }
+
+    fn render_region(
+        &self,
+        triangle: &Triangle,
+        framebuffer: &mut Framebuffer,
+        region: &PixelRect,
+        settings: &RenderSettings,
+    ) {
+        let Some(region) = region.intersection(&framebuffer.region) else {
+            return;
+        };
+
+        if let Some(bounds) = triangle.bounds(framebuffer.width, framebuffer.height) {
+            if let Some(bounds) = bounds.intersection(&region) {
+                fill_triangle(triangle, framebuffer, &bounds, settings);
+            }
+        }
+
+        draw_edges(triangle, framebuffer, &region, settings);
+    }
+}
+
+
+// --------------------
+// valor da função de aresta a -> b no ponto p: positivo quando p está
+// à esquerda da aresta
+// --------------------
+
+fn edge_function(a: [f64; 2], b: [f64; 2], p: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+
+// --------------------
+// regra top-left para o triângulo em sentido anti-horário (com y para
+// cima): um ponto sobre a aresta só é pintado se ela for uma aresta
+// esquerda (descendo) ou superior (horizontal, indo para a esquerda)
+// --------------------
+
+fn is_top_left(a: [f64; 2], b: [f64; 2]) -> bool {
+    let dx = b[0] - a[0];
+    let dy = b[1] - a[1];
+    dy < 0.0 || (dy == 0.0 && dx < 0.0)
+}
+
+
+fn fill_triangle(
+    triangle: &Triangle,
+    framebuffer: &mut Framebuffer,
+    bounds: &PixelRect,
+    settings: &RenderSettings,
+) {
+    if !triangle.is_drawable() {
+        return;
+    }
+
+    let samples = settings.samples();
+
+    let position = |i: usize| {
+        let p = triangle.vertices[i].position;
+        [p[0] as f64, p[1] as f64]
+    };
+
+    // ordena os vértices em sentido anti-horário
+    let mut order = [0, 1, 2];
+    let mut area = edge_function(position(0), position(1), position(2));
+    if area < 0.0 {
+        order = [0, 2, 1];
+        area = -area;
+    }
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+
+    let v = order.map(position);
+    let varyings = vertex_varyings(triangle, settings);
+    let varyings = order.map(|i| varyings[i].clone());
+    let mut values = varyings[0].clone();
+
+    // aresta oposta a cada vértice
+    let edges = [(v[1], v[2]), (v[2], v[0]), (v[0], v[1])];
+    let top_left = edges.map(|(a, b)| is_top_left(a, b));
+
+    let weights = |p: [f64; 2]| [
+        edge_function(edges[0].0, edges[0].1, p),
+        edge_function(edges[1].0, edges[1].1, p),
+        edge_function(edges[2].0, edges[2].1, p),
+    ];
+    let inside = |weights: [f64; 3]| (0..3).all(|i| weights[i] > 0.0 || (weights[i] == 0.0 && top_left[i]));
+
+    let offsets: Vec<f64> = (0..samples).map(|i| (i as f64 + 0.5) / samples as f64).collect();
+    let total = (samples * samples) as f32;
+
+    for y in bounds.min[1]..bounds.max[1] {
+        for x in bounds.min[0]..bounds.max[0] {
+            let mut covered = 0;
+            for &dy in &offsets {
+                for &dx in &offsets {
+                    if inside(weights([x as f64 + dx, y as f64 + dy])) {
+                        covered += 1;
+                    }
+                }
+            }
+            if covered == 0 {
+                continue;
+            }
+
+            let center = weights([x as f64 + 0.5, y as f64 + 0.5]).map(|w| (w / area) as f32);
+            for (k, value) in values.iter_mut().enumerate() {
+                *value = center[0] * varyings[0][k] + center[1] * varyings[1][k] + center[2] * varyings[2][k];
+            }
+
+            shade(triangle, settings, framebuffer, x, y, &mut values, covered as f32 / total);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use super::{
+    scanline::to_u8,
+    Fragment,
+    Framebuffer,
+    ShadingModel,
+    RenderSettings,
+    Triangle,
+};
+
+// --------------------
+// valores interpolados pelo rasterizador ao longo do triângulo
+// (varyings). cada vértice leva um vetor com o layout abaixo, e os dois
+// rasterizadores interpolam todos os valores da mesma forma, sem
+// precisar saber o que cada um significa.
+//
+// com correção de perspectiva, os vértices levam cada valor dividido
+// por w e também 1/w, que variam linearmente na tela; no estágio de
+// fragmentos os valores são recuperados dividindo por 1/w interpolado.
+// a profundidade é sempre interpolada na tela, sem essa divisão.
+//
+// lighting é a iluminação calculada nos vértices (ver PointLight::illuminate),
+// e barycentric começa em (1, 0, 0), (0, 1, 0) e (0, 0, 1) nos três
+// vértices, virando as coordenadas baricêntricas de cada pixel. depois de
+// VARYINGS vêm os atributos próprios dos vértices, na ordem do primeiro
+// vértice (ver Attribute).
+// --------------------
+
+pub(super) const COLOR: usize = 0;
+pub(super) const DEPTH: usize = 4;
+pub(super) const UV: usize = 5;
+pub(super) const NORMAL: usize = 7;
+pub(super) const LIGHTING: usize = 10;
+pub(super) const BARYCENTRIC: usize = 16;
+pub(super) const INVERSE_W: usize = 19;
+pub(super) const VARYINGS: usize = 20;
+
+
+pub(super) fn vertex_varyings(triangle: &Triangle, settings: &RenderSettings) -> [Vec<f32>; 3] {
+    let mut colors = triangle.color_space.encode_vertices(triangle.vertices.each_ref().map(|vertex| vertex.color));
+    let mut normals = triangle.vertices.each_ref().map(|vertex| vertex.normal);
+    let mut points = triangle.vertices.each_ref().map(|vertex| vertex.position);
+
+    // no sombreamento flat, os três vértices recebem a cor média, a
+    // normal média e o centroide
+    if triangle.shading == ShadingModel::Flat {
+        let mean = |values: &[f32; 3]| (values[0] + values[1] + values[2]) / 3.0;
+        let color = [0, 1, 2, 3].map(|i| mean(&colors.map(|color| color[i])));
+        let normal = [0, 1, 2].map(|i| mean(&normals.map(|normal| normal[i])));
+        let point = [0, 1].map(|i| mean(&points.map(|point| point[i])));
+
+        colors = [color; 3];
+        normals = [normal; 3];
+        points = [point; 3];
+    }
+
+    [0, 1, 2].map(|i| {
+        let vertex = &triangle.vertices[i];
+
+        let lighting = match settings.light {
+            Some(light) if triangle.shading != ShadingModel::Phong => light.illuminate(points[i], normals[i]),
+            _ => [1.0, 1.0, 1.0, 0.0, 0.0, 0.0],
+        };
+
+        let mut varyings = Vec::with_capacity(VARYINGS + triangle.vertices[0].attributes.len());
+        varyings.extend(colors[i]);
+        varyings.push(vertex.depth);
+        varyings.extend(vertex.uv);
+        varyings.extend(normals[i]);
+        varyings.extend(lighting);
+        varyings.extend([0, 1, 2].map(|j| if i == j { 1.0 } else { 0.0 }));
+        varyings.push(1.0);
+        varyings.extend(
+            triangle.vertices[0]
+                .attributes
+                .iter()
+                .map(|attribute| vertex.attribute(&attribute.name).unwrap_or(0.0)),
+        );
+
+        if triangle.perspective_correct {
+            for (index, value) in varyings.iter_mut().enumerate() {
+                if index != DEPTH {
+                    *value /= vertex.w;
+                }
+            }
+        }
+
+        varyings
+    })
+}
+
+
+// --------------------
+// estágio de fragmentos: recebe os valores interpolados em um pixel
+// coberto (em uma fração coverage), faz o teste de profundidade, calcula
+// a cor interpolada, passa o pixel pelo shader do triângulo e compõe o
+// resultado no framebuffer. os valores podem ser alterados (pela
+// correção de perspectiva).
+// --------------------
+
+pub(super) fn shade(
+    triangle: &Triangle,
+    settings: &RenderSettings,
+    framebuffer: &mut Framebuffer,
+    x: usize,
+    y: usize,
+    varyings: &mut [f32],
+    coverage: f32,
+) {
+    if settings.depth_test && !framebuffer.depth_test(x, y, varyings[DEPTH]) {
+        return;
+    }
+
+    if triangle.perspective_correct {
+        let inverse_w = varyings[INVERSE_W];
+        for (index, value) in varyings.iter_mut().enumerate() {
+            if index != DEPTH {
+                *value /= inverse_w;
+            }
+        }
+    }
+
+    let mut color = triangle.color_space.decode([
+        varyings[COLOR],
+        varyings[COLOR + 1],
+        varyings[COLOR + 2],
+        varyings[COLOR + 3],
+    ]);
+
+    if let Some(mapping) = &triangle.texture {
+        let texel = mapping.texture.sample([varyings[UV], varyings[UV + 1]], mapping.filter);
+        color = if mapping.modulate {
+            [0, 1, 2, 3].map(|i| texel[i] * color[i] / 255.0)
+        } else {
+            texel
+        };
+    }
+
+    // --------------------
+    // iluminação: no Phong é calculada aqui, com a normal interpolada;
+    // nos outros modelos já vem interpolada dos vértices
+    // --------------------
+
+    if let Some(light) = settings.light {
+        let lighting = if triangle.shading == ShadingModel::Phong {
+            let normal = [varyings[NORMAL], varyings[NORMAL + 1], varyings[NORMAL + 2]];
+            light.illuminate([x as f32 + 0.5, y as f32 + 0.5], normal)
+        } else {
+            [0, 1, 2, 3, 4, 5].map(|i| varyings[LIGHTING + i])
+        };
+
+        for channel in 0..3 {
+            color[channel] = color[channel] * lighting[channel] + lighting[3 + channel] * 255.0;
+        }
+    }
+
+    let mut color = triangle.shader.shade(&Fragment {
+        position: [x as f32 + 0.5, y as f32 + 0.5],
+        barycentric: [0, 1, 2].map(|i| varyings[BARYCENTRIC + i]),
+        color,
+        uv: [varyings[UV], varyings[UV + 1]],
+        normal: [varyings[NORMAL], varyings[NORMAL + 1], varyings[NORMAL + 2]],
+        depth: varyings[DEPTH],
+        attributes: &varyings[VARYINGS..],
+        layout: &triangle.vertices[0].attributes,
+    });
+
+    color[3] *= coverage;
+
+    framebuffer.blend_pixel(x, y, color.map(to_u8), triangle.blend_mode);
+}
@@ -0,0 +1,153 @@
+use super::{
+    BlendMode,
+    PixelRect,
+};
+
+// --------------------
+// buffer de pixels RGBA8, armazenado linha a linha a partir do topo
+// (mesmo layout dos dados de uma textura). as coordenadas recebidas
+// pelos métodos têm origem no canto inferior esquerdo, como as
+// posições dos vértices.
+//
+// width e height são o tamanho da tela inteira, usado no recorte.
+// region é a parte da tela guardada em pixels: a tela toda em um
+// framebuffer comum, ou apenas um pedaço em um ladrilho (tile).
+//
+// depth é o buffer de profundidade (Z-buffer), com um valor por pixel
+// no mesmo layout. quanto maior o z, mais próximo do observador; um
+// pixel vazio tem profundidade -infinito.
+// --------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Framebuffer {
+    pub width: usize,
+    pub height: usize,
+    pub region: PixelRect,
+    pub pixels: Vec<u8>,
+    pub depth: Vec<f32>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::tile(width, height, PixelRect::new(width, height))
+    }
+
+    pub fn tile(width: usize, height: usize, region: PixelRect) -> Self {
+        let region = region.intersection(&PixelRect::new(width, height)).unwrap_or(PixelRect {
+            min: [0, 0],
+            max: [0, 0],
+        });
+
+        Self {
+            width,
+            height,
+            region,
+            pixels: vec![0; region.width() * region.height() * 4],
+            depth: vec![f32::NEG_INFINITY; region.width() * region.height()],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.pixels.fill(0);
+        self.depth.fill(f32::NEG_INFINITY);
+    }
+
+    pub fn clear_region(&mut self, region: &PixelRect) {
+        let Some(region) = region.intersection(&self.region) else {
+            return;
+        };
+
+        for y in region.min[1]..region.max[1] {
+            let start = self.index(region.min[0], y);
+            let end = start + region.width() * 4;
+            self.pixels[start..end].fill(0);
+            self.depth[start / 4..end / 4].fill(f32::NEG_INFINITY);
+        }
+    }
+
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        let i = self.region.max[1] - y - 1;
+        let j = x - self.region.min[0];
+        (i * self.region.width() + j) * 4
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let index = self.index(x, y);
+        [
+            self.pixels[index],
+            self.pixels[index + 1],
+            self.pixels[index + 2],
+            self.pixels[index + 3],
+        ]
+    }
+
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: [u8; 4]) {
+        let index = self.index(x, y);
+        self.pixels[index..index + 4].copy_from_slice(&color);
+    }
+
+    // --------------------
+    // compõe color sobre o pixel atual com o modo de mistura dado
+    // (alfa não pré-multiplicado)
+    // --------------------
+
+    pub fn blend_pixel(&mut self, x: usize, y: usize, color: [u8; 4], mode: BlendMode) {
+        let blended = mode.blend(color, self.pixel(x, y));
+        self.set_pixel(x, y, blended);
+    }
+
+    // --------------------
+    // teste de profundidade: se depth estiver à frente do valor guardado
+    // (ou empatar com ele, para que triângulos coplanares sigam a ordem
+    // de pintura), grava depth e devolve true
+    // --------------------
+
+    pub fn depth_test(&mut self, x: usize, y: usize, depth: f32) -> bool {
+        let index = self.index(x, y) / 4;
+        if depth >= self.depth[index] {
+            self.depth[index] = depth;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn rect(&self) -> PixelRect {
+        PixelRect::new(self.width, self.height)
+    }
+
+    // --------------------
+    // copia apenas os pixels de region para target, que deve ter o
+    // mesmo tamanho e layout deste framebuffer
+    // --------------------
+
+    pub fn copy_region_to(&self, region: &PixelRect, target: &mut [u8]) {
+        let Some(region) = region.intersection(&self.region) else {
+            return;
+        };
+
+        for y in region.min[1]..region.max[1] {
+            let start = self.index(region.min[0], y);
+            let end = start + region.width() * 4;
+            target[start..end].copy_from_slice(&self.pixels[start..end]);
+        }
+    }
+
+    // --------------------
+    // copia os pixels de um ladrilho para a posição correspondente
+    // --------------------
+
+    pub fn blit(&mut self, tile: &Framebuffer) {
+        let Some(region) = tile.region.intersection(&self.region) else {
+            return;
+        };
+
+        for y in region.min[1]..region.max[1] {
+            let source = tile.index(region.min[0], y);
+            let target = self.index(region.min[0], y);
+            let length = region.width() * 4;
+            self.pixels[target..target + length].copy_from_slice(&tile.pixels[source..source + length]);
+            self.depth[target / 4..(target + length) / 4].copy_from_slice(&tile.depth[source / 4..(source + length) / 4]);
+        }
+    }
+}
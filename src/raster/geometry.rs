@@ -0,0 +1,237 @@
+use std::sync::Arc;
+
+use super::{
+    BlendMode,
+    ColorSpace,
+    FragmentShader,
+    ShadingModel,
+    TextureMapping,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vertex {
+    pub color: [u8; 4],
+    pub position: [f32; 2],
+    pub depth: f32,
+    pub uv: [f32; 2],
+    // coordenada homogênea: position já é a projeção dividida por w
+    pub w: f32,
+    pub normal: [f32; 3],
+    pub attributes: Vec<Attribute>,
+}
+
+// --------------------
+// vértice com os valores padrão: sem profundidade nem textura, w = 1
+// (sem perspectiva), normal voltada para o observador e sem atributos
+// --------------------
+
+impl Default for Vertex {
+    fn default() -> Self {
+        Self {
+            color: [0, 0, 0, 255],
+            position: [0.0, 0.0],
+            depth: 0.0,
+            uv: [0.0, 0.0],
+            w: 1.0,
+            normal: [0.0, 0.0, 1.0],
+            attributes: Vec::new(),
+        }
+    }
+}
+
+impl Vertex {
+    pub fn new(position: [f32; 2], color: [u8; 4]) -> Self {
+        Self {
+            position,
+            color,
+            ..Default::default()
+        }
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<f32> {
+        self.attributes.iter().find(|attribute| attribute.name == name).map(|attribute| attribute.value)
+    }
+}
+
+
+// --------------------
+// atributo próprio de um vértice (temperatura, material etc.), interpolado
+// pelo rasterizador e entregue ao shader de fragmentos. os atributos do
+// primeiro vértice definem quais são interpolados; nos outros vértices
+// eles são procurados pelo nome, valendo 0 quando não existem.
+// --------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub name: String,
+    pub value: f32,
+}
+
+impl Attribute {
+    pub fn new(name: impl Into<String>, value: f32) -> Self {
+        Self {
+            name: name.into(),
+            value,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Triangle {
+    pub vertices: [Vertex; 3],
+    pub edges_color: Option<[u8; 3]>,
+    pub blend_mode: BlendMode,
+    pub color_space: ColorSpace,
+    pub texture: Option<TextureMapping>,
+    pub perspective_correct: bool,
+    pub shading: ShadingModel,
+    pub shader: Arc<dyn FragmentShader>,
+}
+
+impl Triangle {
+    // --------------------
+    // vértices fora do plano (NaN ou infinito) não podem ser pintados, e
+    // a correção de perspectiva exige w positivo (à frente do observador)
+    // --------------------
+
+    pub fn is_drawable(&self) -> bool {
+        self.vertices.iter().all(|vertex| {
+            vertex.position.iter().all(|c| c.is_finite())
+                && (!self.perspective_correct || (vertex.w > 0.0 && vertex.w.is_finite()))
+        })
+    }
+
+    // --------------------
+    // retângulo de pixels que o triângulo pode pintar, já limitado
+    // a um framebuffer de tamanho width x height
+    // --------------------
+
+    pub fn bounds(&self, width: usize, height: usize) -> Option<PixelRect> {
+        vertex_bounds(&self.vertices, self.edges_color.is_some(), width, height)
+    }
+
+    pub fn contains(&self, point: (f32, f32)) -> bool {
+        let [a, b, c] = &self.vertices;
+        let (u, v, _w) = barycentric_coordinates(
+            point,
+            (a.position[0], a.position[1]),
+            (b.position[0], b.position[1]),
+            (c.position[0], c.position[1]),
+        );
+
+        u >= 0.0 && v >= 0.0 && u + v <= 1.0
+    }
+}
+
+
+// --------------------
+// retângulo de pixels que cobre os vértices, limitado a um framebuffer
+// de tamanho width x height. as arestas antisserrilhadas (edges) podem
+// pintar um pixel além dos vértices.
+// --------------------
+
+pub(super) fn vertex_bounds<'a>(
+    vertices: impl IntoIterator<Item = &'a Vertex>,
+    edges: bool,
+    width: usize,
+    height: usize,
+) -> Option<PixelRect> {
+    let mut min = [f32::INFINITY; 2];
+    let mut max = [f32::NEG_INFINITY; 2];
+
+    for vertex in vertices {
+        if !vertex.position.iter().all(|c| c.is_finite()) {
+            return None;
+        }
+        for axis in 0..2 {
+            min[axis] = min[axis].min(vertex.position[axis]);
+            max[axis] = max[axis].max(vertex.position[axis]);
+        }
+    }
+
+    let margin = if edges { 1.0 } else { 0.0 };
+
+    let clamp = |value: f32, limit: usize| value.clamp(0.0, limit as f32) as usize;
+
+    let rect = PixelRect {
+        min: [clamp(min[0].floor() - margin, width), clamp(min[1].floor() - margin, height)],
+        max: [clamp(max[0].floor() + 1.0 + margin, width), clamp(max[1].floor() + 1.0 + margin, height)],
+    };
+
+    if rect.is_empty() { None } else { Some(rect) }
+}
+
+
+// --------------------
+// retângulo de pixels [min, max), com origem no canto inferior esquerdo
+// --------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelRect {
+    pub min: [usize; 2],
+    pub max: [usize; 2],
+}
+
+impl PixelRect {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            min: [0, 0],
+            max: [width, height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.max[0].saturating_sub(self.min[0])
+    }
+
+    pub fn height(&self) -> usize {
+        self.max[1].saturating_sub(self.min[1])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
+    }
+
+    pub fn union(&self, other: &PixelRect) -> PixelRect {
+        PixelRect {
+            min: [self.min[0].min(other.min[0]), self.min[1].min(other.min[1])],
+            max: [self.max[0].max(other.max[0]), self.max[1].max(other.max[1])],
+        }
+    }
+
+    pub fn intersection(&self, other: &PixelRect) -> Option<PixelRect> {
+        let rect = PixelRect {
+            min: [self.min[0].max(other.min[0]), self.min[1].max(other.min[1])],
+            max: [self.max[0].min(other.max[0]), self.max[1].min(other.max[1])],
+        };
+
+        if rect.is_empty() { None } else { Some(rect) }
+    }
+
+    pub fn intersects(&self, other: &PixelRect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.min[0] && x < self.max[0] && y >= self.min[1] && y < self.max[1]
+    }
+}
+
+
+pub fn barycentric_coordinates(p: (f32, f32), a: (f32, f32), b: (f32, f32), c: (f32, f32)) -> (f32, f32, f32) {
+    let v0 = (c.0 - a.0, c.1 - a.1);
+    let v1 = (b.0 - a.0, b.1 - a.1);
+    let v2 = (p.0 - a.0, p.1 - a.1);
+
+    let dot00 = v0.0 * v0.0 + v0.1 * v0.1;
+    let dot01 = v0.0 * v1.0 + v0.1 * v1.1;
+    let dot02 = v0.0 * v2.0 + v0.1 * v2.1;
+    let dot11 = v1.0 * v1.0 + v1.1 * v1.1;
+    let dot12 = v1.0 * v2.0 + v1.1 * v2.1;
+
+    let inv_denom = 1.0 / (dot00 * dot11 - dot01 * dot01);
+    let u = (dot11 * dot02 - dot01 * dot12) * inv_denom;
+    let v = (dot00 * dot12 - dot01 * dot02) * inv_denom;
+
+    (u, v, 1.0 - u - v)
+}
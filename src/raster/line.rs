@@ -0,0 +1,469 @@
+use super::{
+    barycentric_coordinates,
+    BlendMode,
+    clip::clip_segment,
+    scanline::to_u8,
+    Framebuffer,
+    PixelRect,
+    RenderSettings,
+    Triangle,
+};
+
+// --------------------
+// algoritmo usado para pintar as arestas com cor constante
+// --------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineAlgorithm {
+    #[default]
+    Bresenham,
+    Dda,
+    XiaolinWu,
+}
+
+impl LineAlgorithm {
+    pub const ALL: [LineAlgorithm; 3] = [
+        LineAlgorithm::Bresenham,
+        LineAlgorithm::Dda,
+        LineAlgorithm::XiaolinWu,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LineAlgorithm::Bresenham => "Bresenham",
+            LineAlgorithm::Dda => "DDA",
+            LineAlgorithm::XiaolinWu => "Xiaolin Wu",
+        }
+    }
+
+    // --------------------
+    // pixels da linha entre p0 e p1 (em coordenadas contínuas, com o
+    // centro do pixel (x, y) em (x + 0.5, y + 0.5)) e a intensidade de
+    // cada um. só o algoritmo de Wu gera intensidades fracionárias.
+    // --------------------
+
+    pub fn pixels(&self, p0: [f32; 2], p1: [f32; 2]) -> Vec<(i64, i64, f32)> {
+        match self {
+            LineAlgorithm::Bresenham => bresenham(p0[0].floor(), p0[1].floor(), p1[0].floor(), p1[1].floor())
+                .into_iter()
+                .map(|(x, y)| (x as i64, y as i64, 1.0))
+                .collect(),
+            LineAlgorithm::Dda => dda(p0[0] - 0.5, p0[1] - 0.5, p1[0] - 0.5, p1[1] - 0.5)
+                .into_iter()
+                .map(|(x, y)| (x as i64, y as i64, 1.0))
+                .collect(),
+            LineAlgorithm::XiaolinWu => xiaolin_wu(p0[0] - 0.5, p0[1] - 0.5, p1[0] - 0.5, p1[1] - 0.5),
+        }
+    }
+}
+
+
+// --------------------
+// pinta as arestas com uma cor constante caso o triângulo possua,
+// compondo sobre o interior já pintado
+// --------------------
+
+pub(super) fn draw_edges(
+    triangle: &Triangle,
+    framebuffer: &mut Framebuffer,
+    region: &PixelRect,
+    settings: &RenderSettings,
+) {
+    let Some(edges_color) = triangle.edges_color else {
+        return;
+    };
+
+    let points = triangle.vertices.each_ref().map(|vertex| vertex.position);
+
+    draw_outline(&points, edges_color, triangle.blend_mode, framebuffer, region, settings, |x, y| {
+        edge_depth(triangle, x, y)
+    });
+}
+
+
+// --------------------
+// pinta o contorno fechado que liga os pontos, na ordem, com uma cor
+// constante. depth dá a profundidade de cada pixel para o Z-buffer.
+// --------------------
+
+pub(super) fn draw_outline(
+    points: &[[f32; 2]],
+    color: [u8; 3],
+    blend_mode: BlendMode,
+    framebuffer: &mut Framebuffer,
+    region: &PixelRect,
+    settings: &RenderSettings,
+    depth: impl Fn(i64, i64) -> f32,
+) {
+    let min = [0.0, 0.0];
+    let max = [framebuffer.width as f32, framebuffer.height as f32];
+
+    // evita que um ponto exatamente sobre a borda direita ou superior caia fora da tela
+    let limit = |position: [f32; 2]| [
+        position[0].clamp(0.0, max[0] - 0.5),
+        position[1].clamp(0.0, max[1] - 0.5),
+    ];
+
+    for i in 0..points.len() {
+        let p0 = points[i];
+        let p1 = points[(i + 1) % points.len()];
+
+        if let Some((p0, p1)) = clip_segment(p0, p1, min, max) {
+            for (x, y, intensity) in settings.line_algorithm.pixels(limit(p0), limit(p1)) {
+                if x < 0 || y < 0 || !region.contains(x as usize, y as usize) {
+                    continue;
+                }
+                if settings.depth_test && !framebuffer.depth_test(x as usize, y as usize, depth(x, y)) {
+                    continue;
+                }
+
+                framebuffer.blend_pixel(
+                    x as usize,
+                    y as usize,
+                    [color[0], color[1], color[2], to_u8(intensity * 255.0)],
+                    blend_mode,
+                );
+            }
+        }
+    }
+}
+
+
+// --------------------
+// profundidade de um pixel da aresta: o plano do triângulo avaliado no
+// centro do pixel, deslocado um pouco para a frente (como o polygon
+// offset do OpenGL) para que a aresta não seja escondida pelo próprio
+// interior por erros de arredondamento
+// --------------------
+
+const EDGE_DEPTH_OFFSET: f32 = 1e-3;
+
+fn edge_depth(triangle: &Triangle, x: i64, y: i64) -> f32 {
+    let [a, b, c] = &triangle.vertices;
+    let (u, v, w) = barycentric_coordinates(
+        (x as f32 + 0.5, y as f32 + 0.5),
+        (a.position[0], a.position[1]),
+        (b.position[0], b.position[1]),
+        (c.position[0], c.position[1]),
+    );
+
+    let mut depth = w * a.depth + v * b.depth + u * c.depth;
+
+    // triângulos degenerados não têm plano
+    if !depth.is_finite() {
+        depth = a.depth.max(b.depth).max(c.depth);
+    }
+
+    offset_depth(depth)
+}
+
+pub(super) fn offset_depth(depth: f32) -> f32 {
+    depth + EDGE_DEPTH_OFFSET * (1.0 + depth.abs())
+}
+
+
+pub fn bresenham(x0: f32, y0: f32, x1: f32, y1: f32) -> Vec<(f32, f32)> {
+    let x0 = x0.round();
+    let y0 = y0.round();
+    let x1 = x1.round();
+    let y1 = y1.round();
+
+    let mut result = Vec::new();
+
+    let dx = (x1 - x0).abs();
+    let dy = (y1 - y0).abs();
+
+    let sx = if x0 < x1 { 1.0 } else { -1.0 };
+    let sy = if y0 < y1 { 1.0 } else { -1.0 };
+
+    let mut x = x0;
+    let mut y = y0;
+
+    let mut err = dx - dy;
+
+    while (x - x1).abs() > 0.1 || (y - y1).abs() > 0.1 {
+        result.push((x, y));
+
+        let e2 = 2.0 * err;
+
+        if e2 > -dy {
+            err -= dy;
+            x += sx;
+        }
+
+        if e2 < dx {
+            err += dx;
+            y += sy;
+        }
+    }
+
+    result.push((x1, y1));
+    result
+}
+
+
+// --------------------
+// DDA: anda um pixel por vez no eixo de maior variação, somando o
+// incremento no outro eixo e arredondando
+// --------------------
+
+pub fn dda(x0: f32, y0: f32, x1: f32, y1: f32) -> Vec<(f32, f32)> {
+    let dx = x1 - x0;
+    let dy = y1 - y0;
+    let steps = dx.abs().max(dy.abs()).round() as usize;
+
+    if steps == 0 {
+        return vec![(x0.round(), y0.round())];
+    }
+
+    let x_increment = dx / steps as f32;
+    let y_increment = dy / steps as f32;
+
+    let mut x = x0;
+    let mut y = y0;
+    let mut result = Vec::with_capacity(steps + 1);
+
+    for _ in 0..=steps {
+        result.push((x.round(), y.round()));
+        x += x_increment;
+        y += y_increment;
+    }
+
+    result
+}
+
+
+// --------------------
+// linha antisserrilhada de Xiaolin Wu: em cada passo no eixo principal,
+// divide a intensidade entre os dois pixels vizinhos no eixo secundário
+// conforme a distância até a linha
+// --------------------
+
+pub fn xiaolin_wu(x0: f32, y0: f32, x1: f32, y1: f32) -> Vec<(i64, i64, f32)> {
+    let fpart = |value: f32| value - value.floor();
+    let rfpart = |value: f32| 1.0 - fpart(value);
+
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+
+    let (mut x0, mut y0, mut x1, mut y1) = if steep {
+        (y0, x0, y1, x1)
+    } else {
+        (x0, y0, x1, y1)
+    };
+
+    if x0 > x1 {
+        std::mem::swap(&mut x0, &mut x1);
+        std::mem::swap(&mut y0, &mut y1);
+    }
+
+    let dx = x1 - x0;
+    let dy = y1 - y0;
+    let gradient = if dx == 0.0 { 1.0 } else { dy / dx };
+
+    let mut result = Vec::new();
+    let mut plot = |x: i64, y: i64, intensity: f32| {
+        if intensity > 0.0 {
+            if steep {
+                result.push((y, x, intensity));
+            } else {
+                result.push((x, y, intensity));
+            }
+        }
+    };
+
+    // primeiro extremo
+    let x_end = x0.round();
+    let y_end = y0 + gradient * (x_end - x0);
+    let x_gap = rfpart(x0 + 0.5);
+    let x_start = x_end as i64;
+    plot(x_start, y_end.floor() as i64, rfpart(y_end) * x_gap);
+    plot(x_start, y_end.floor() as i64 + 1, fpart(y_end) * x_gap);
+    let mut y = y_end + gradient;
+
+    // segundo extremo
+    let x_end = x1.round();
+    let y_end = y1 + gradient * (x_end - x1);
+    let x_gap = fpart(x1 + 0.5);
+    let x_last = x_end as i64;
+    if x_last != x_start {
+        plot(x_last, y_end.floor() as i64, rfpart(y_end) * x_gap);
+        plot(x_last, y_end.floor() as i64 + 1, fpart(y_end) * x_gap);
+    }
+
+    // pontos intermediários
+    for x in (x_start + 1)..x_last {
+        plot(x, y.floor() as i64, rfpart(y));
+        plot(x, y.floor() as i64 + 1, fpart(y));
+        y += gradient;
+    }
+
+    result
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    // --------------------
+    // uma linha saindo do centro para cada octante, mais as horizontais,
+    // verticais e diagonais
+    // --------------------
+
+    const CENTER: (f32, f32) = (20.0, 20.0);
+
+    const ENDS: [(f32, f32); 16] = [
+        (27.0, 23.0), (23.0, 27.0), (17.0, 27.0), (13.0, 23.0),
+        (13.0, 17.0), (17.0, 13.0), (23.0, 13.0), (27.0, 17.0),
+        (28.0, 20.0), (20.0, 28.0), (12.0, 20.0), (20.0, 12.0),
+        (26.0, 26.0), (14.0, 26.0), (14.0, 14.0), (26.0, 14.0),
+    ];
+
+    // --------------------
+    // os pixels vão de um extremo ao outro, um por passo no eixo
+    // principal, e nenhum fica a mais de meio pixel da linha ideal
+    // --------------------
+
+    fn assert_is_line(pixels: &[(f32, f32)], (x0, y0): (f32, f32), (x1, y1): (f32, f32), name: &str) {
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let steep = dy.abs() > dx.abs();
+        let steps = dx.abs().max(dy.abs()) as usize;
+
+        assert_eq!(pixels.first(), Some(&(x0, y0)), "{name}: início de {:?}", (x1, y1));
+        assert_eq!(pixels.last(), Some(&(x1, y1)), "{name}: fim de {:?}", (x1, y1));
+        assert_eq!(pixels.len(), steps + 1, "{name}: {:?}", pixels);
+
+        for (i, &(x, y)) in pixels.iter().enumerate() {
+            let t = i as f32 / steps as f32;
+            let (major, minor, ideal) = if steep {
+                (y, x, x0 + dx * t)
+            } else {
+                (x, y, y0 + dy * t)
+            };
+            let expected_major = if steep { y0 + dy * t } else { x0 + dx * t };
+
+            assert_eq!(major, expected_major, "{name}: {:?}", pixels);
+            assert!((minor - ideal).abs() <= 0.5 + 1e-4, "{name}: ({x}, {y}) longe da linha até {:?}", (x1, y1));
+        }
+    }
+
+    #[test]
+    fn bresenham_and_dda_cover_every_octant() {
+        for end in ENDS {
+            assert_is_line(&bresenham(CENTER.0, CENTER.1, end.0, end.1), CENTER, end, "Bresenham");
+            assert_is_line(&dda(CENTER.0, CENTER.1, end.0, end.1), CENTER, end, "DDA");
+        }
+    }
+
+    #[test]
+    fn shallow_and_steep_lines_have_the_expected_pixels() {
+        let shallow = [(0.0, 0.0), (1.0, 0.0), (2.0, 1.0), (3.0, 1.0), (4.0, 2.0), (5.0, 2.0)];
+        let steep = shallow.map(|(x, y)| (y, x));
+
+        assert_eq!(bresenham(0.0, 0.0, 5.0, 2.0), shallow);
+        assert_eq!(dda(0.0, 0.0, 5.0, 2.0), shallow);
+        assert_eq!(bresenham(0.0, 0.0, 2.0, 5.0), steep);
+        assert_eq!(dda(0.0, 0.0, 2.0, 5.0), steep);
+
+        // um único ponto
+        assert_eq!(bresenham(3.0, 4.0, 3.0, 4.0), [(3.0, 4.0)]);
+        assert_eq!(dda(3.0, 4.0, 3.0, 4.0), [(3.0, 4.0)]);
+    }
+
+    #[test]
+    fn xiaolin_wu_splits_each_step_between_two_pixels() {
+        for end in ENDS {
+            // extremos fora dos centros de pixel, para que as frações não sejam triviais
+            let (x0, y0) = (CENTER.0 + 0.3, CENTER.1 - 0.2);
+            let (x1, y1) = (end.0 - 0.1, end.1 + 0.4);
+            let steep = (y1 - y0).abs() > (x1 - x0).abs();
+
+            let pixels = xiaolin_wu(x0, y0, x1, y1);
+
+            let mut steps: HashMap<i64, Vec<(i64, f32)>> = HashMap::new();
+            for (x, y, intensity) in pixels {
+                assert!(intensity > 0.0 && intensity <= 1.0);
+                let (major, minor) = if steep { (y, x) } else { (x, y) };
+                steps.entry(major).or_default().push((minor, intensity));
+            }
+
+            // os extremos são atenuados pela parte do pixel que a linha cobre;
+            // os passos intermediários somam intensidade 1 em dois vizinhos
+            let (first, last) = if steep {
+                (y0.min(y1).round() as i64, y0.max(y1).round() as i64)
+            } else {
+                (x0.min(x1).round() as i64, x0.max(x1).round() as i64)
+            };
+            assert_eq!(steps.len() as i64, last - first + 1, "{:?}", end);
+
+            for major in (first + 1)..last {
+                let step = &steps[&major];
+                let total: f32 = step.iter().map(|(_, intensity)| intensity).sum();
+
+                assert!((total - 1.0).abs() < 1e-3, "{:?}, passo {major}: {:?}", end, step);
+                assert!(step.len() <= 2);
+                if let [(a, _), (b, _)] = step[..] {
+                    assert_eq!((a - b).abs(), 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn outline_is_blended_over_the_interior() {
+        let settings = RenderSettings {
+            line_algorithm: LineAlgorithm::XiaolinWu,
+            ..Default::default()
+        };
+
+        let mut framebuffer = Framebuffer::new(32, 32);
+        for y in 0..32 {
+            for x in 0..32 {
+                framebuffer.set_pixel(x, y, [255, 0, 0, 255]);
+            }
+        }
+        let region = framebuffer.region;
+
+        let points = [[4.3, 5.1], [27.6, 12.8], [9.2, 26.4]];
+        draw_outline(&points, [0, 0, 255], BlendMode::Normal, &mut framebuffer, &region, &settings, |_, _| 0.0);
+
+        // onde a linha é fraca, o vermelho do interior continua aparecendo
+        let mut partial = 0;
+        for y in 0..32 {
+            for x in 0..32 {
+                let [red, green, blue, alpha] = framebuffer.pixel(x, y);
+
+                assert_eq!(alpha, 255, "({x}, {y})");
+                assert_eq!(green, 0);
+                assert!((red as i32 + blue as i32 - 255).abs() <= 1, "({x}, {y}): {red} + {blue}");
+                if red > 20 && blue > 20 {
+                    partial += 1;
+                }
+            }
+        }
+        assert!(partial > 10);
+    }
+
+    #[test]
+    fn edges_in_front_of_their_own_interior_pass_the_depth_test() {
+        let depth = 3.7;
+        assert!(offset_depth(depth) > depth);
+        assert!(offset_depth(-depth) > -depth);
+
+        let settings = RenderSettings {
+            depth_test: true,
+            ..Default::default()
+        };
+        let mut framebuffer = Framebuffer::new(8, 8);
+        let region = framebuffer.region;
+
+        // o interior já gravou no pixel uma profundidade com um pequeno erro
+        // de arredondamento para a frente; a aresta ainda passa por cima
+        assert!(framebuffer.depth_test(2, 2, depth + 1e-4));
+        assert!(!framebuffer.clone().depth_test(2, 2, depth));
+        draw_outline(&[[2.5, 2.5], [2.5, 2.5]], [0, 255, 0], BlendMode::Normal, &mut framebuffer, &region, &settings, |_, _| offset_depth(depth));
+        assert_eq!(framebuffer.pixel(2, 2), [0, 255, 0, 255]);
+    }
+}
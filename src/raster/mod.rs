@@ -0,0 +1,91 @@
+// --------------------
+// rasterizador independente da Bevy: recebe geometria simples
+// e pinta em um Framebuffer RGBA
+// --------------------
+
+mod blend;
+mod clip;
+mod color;
+mod edge_function;
+mod fragment;
+mod framebuffer;
+mod geometry;
+mod line;
+mod polygon;
+mod primitive;
+mod rasterizer;
+mod scanline;
+mod shader;
+mod shading;
+mod texture;
+mod tiles;
+mod triangulation;
+
+pub use blend::BlendMode;
+pub use color::ColorSpace;
+pub use edge_function::EdgeFunction;
+
+pub use framebuffer::Framebuffer;
+pub use geometry::{
+    barycentric_coordinates,
+    Attribute,
+    PixelRect,
+    Triangle,
+    Vertex,
+};
+pub use polygon::{
+    render_polygon,
+    render_polygon_region,
+    FillRule,
+    Polygon,
+};
+pub use primitive::{
+    Primitive,
+    Shape,
+};
+pub use rasterizer::{
+    rasterizers,
+    Rasterizer,
+    RenderSettings,
+    ANTIALIASING_SAMPLES,
+};
+pub use line::{
+    bresenham,
+    dda,
+    xiaolin_wu,
+    LineAlgorithm,
+};
+pub use scanline::{
+    render,
+    render_region,
+    Scanline,
+};
+pub use shader::{
+    shaders,
+    Barycentric,
+    Checkerboard,
+    Fragment,
+    FragmentShader,
+    Gouraud,
+    Heatmap,
+    Stripes,
+};
+pub use shading::{
+    PointLight,
+    ShadingModel,
+};
+pub use texture::{
+    Texture,
+    TextureFilter,
+    TextureMapping,
+};
+pub use tiles::{
+    bin_triangles,
+    render_tiled,
+    spawn_threads,
+    Tile,
+};
+pub use triangulation::{
+    delaunay,
+    ear_clipping,
+};
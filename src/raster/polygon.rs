@@ -0,0 +1,336 @@
+use super::{
+    clip::{
+        clip_polygon,
+        ClipVertex,
+    },
+    geometry::vertex_bounds,
+    line::{
+        draw_outline,
+        offset_depth,
+    },
+    scanline::{
+        fill_polygon,
+        to_u8,
+    },
+    BlendMode,
+    Framebuffer,
+    PixelRect,
+    RenderSettings,
+    Vertex,
+};
+
+// --------------------
+// regra que decide quais pontos estão dentro de um contorno que se
+// cruza ou dá voltas, a partir do winding number (quantas vezes o
+// contorno gira em volta do ponto, com sinal):
+// - par-ímpar: dentro se o contorno é cruzado um número ímpar de vezes
+// - não nulo: dentro se o winding number é diferente de zero
+// --------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FillRule {
+    #[default]
+    EvenOdd,
+    NonZero,
+}
+
+impl FillRule {
+    pub const ALL: [FillRule; 2] = [FillRule::EvenOdd, FillRule::NonZero];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FillRule::EvenOdd => "Par-ímpar",
+            FillRule::NonZero => "Não nulo",
+        }
+    }
+
+    pub fn is_inside(&self, winding: i32) -> bool {
+        match self {
+            FillRule::EvenOdd => winding % 2 != 0,
+            FillRule::NonZero => winding != 0,
+        }
+    }
+}
+
+
+// --------------------
+// polígono com qualquer número de vértices, ligados na ordem e fechado
+// do último para o primeiro. pode ser côncavo ou se cruzar; fill_rule
+// decide o que é interior. as cores e as profundidades dos vértices são
+// interpoladas ao longo das arestas e dos intervalos de cada linha.
+//
+// holes são contornos internos (furos), fechados da mesma forma. eles
+// são sempre percorridos no sentido contrário ao do contorno externo,
+// qualquer que seja a ordem em que foram desenhados, para que também
+// fiquem vazios com a regra não nula.
+// --------------------
+
+#[derive(Debug, Clone)]
+pub struct Polygon {
+    pub vertices: Vec<Vertex>,
+    pub holes: Vec<Vec<Vertex>>,
+    pub edges_color: Option<[u8; 3]>,
+    pub blend_mode: BlendMode,
+    pub fill_rule: FillRule,
+}
+
+impl Polygon {
+    pub fn is_drawable(&self) -> bool {
+        self.vertices.len() >= 3
+            && self.rings().flatten().all(|vertex| vertex.position.iter().all(|c| c.is_finite()))
+    }
+
+    pub fn bounds(&self, width: usize, height: usize) -> Option<PixelRect> {
+        vertex_bounds(self.rings().flatten(), self.edges_color.is_some(), width, height)
+    }
+
+    // contorno externo seguido dos furos
+    pub fn rings(&self) -> impl Iterator<Item = &Vec<Vertex>> {
+        std::iter::once(&self.vertices).chain(&self.holes)
+    }
+
+    // --------------------
+    // contornos prontos para a pintura: os furos com no mínimo três
+    // vértices, virados para o sentido contrário ao do contorno externo
+    // --------------------
+
+    fn oriented_rings(&self) -> Vec<Vec<[f32; 2]>> {
+        let orientation = signed_area(&self.vertices).signum();
+
+        self.rings()
+            .enumerate()
+            .filter(|(index, ring)| *index == 0 || ring.len() >= 3)
+            .map(|(index, ring)| {
+                let mut points: Vec<[f32; 2]> = ring.iter().map(|vertex| vertex.position).collect();
+                if index > 0 && signed_area(ring).signum() == orientation {
+                    points.reverse();
+                }
+                points
+            })
+            .collect()
+    }
+
+    // --------------------
+    // winding number dos contornos em volta de point: soma +1 para cada
+    // aresta que sobe à direita do ponto e -1 para cada uma que desce
+    // --------------------
+
+    pub fn winding_number(&self, point: (f32, f32)) -> i32 {
+        let mut winding = 0;
+
+        for ring in self.oriented_rings() {
+            for i in 0..ring.len() {
+                let a = ring[i];
+                let b = ring[(i + 1) % ring.len()];
+                let side = (b[0] - a[0]) * (point.1 - a[1]) - (point.0 - a[0]) * (b[1] - a[1]);
+
+                if a[1] <= point.1 && b[1] > point.1 && side > 0.0 {
+                    winding += 1;
+                } else if a[1] > point.1 && b[1] <= point.1 && side < 0.0 {
+                    winding -= 1;
+                }
+            }
+        }
+
+        winding
+    }
+
+    pub fn contains(&self, point: (f32, f32)) -> bool {
+        self.fill_rule.is_inside(self.winding_number(point))
+    }
+}
+
+
+// --------------------
+// área com sinal (fórmula do laço): positiva no sentido anti-horário
+// --------------------
+
+fn signed_area(vertices: &[Vertex]) -> f32 {
+    let mut area = 0.0;
+
+    for i in 0..vertices.len() {
+        let a = vertices[i].position;
+        let b = vertices[(i + 1) % vertices.len()].position;
+        area += a[0] * b[1] - b[0] * a[1];
+    }
+
+    area / 2.0
+}
+
+
+// --------------------
+// os polígonos são sempre pintados pela scanline com tabela de arestas,
+// qualquer que seja o rasterizador escolhido para os triângulos
+// --------------------
+
+const COLOR: usize = 0;
+const DEPTH: usize = 4;
+
+pub fn render_polygon(polygon: &Polygon, framebuffer: &mut Framebuffer, settings: &RenderSettings) {
+    let region = framebuffer.region;
+    render_polygon_region(polygon, framebuffer, &region, settings);
+}
+
+pub fn render_polygon_region(
+    polygon: &Polygon,
+    framebuffer: &mut Framebuffer,
+    region: &PixelRect,
+    settings: &RenderSettings,
+) {
+    let Some(region) = region.intersection(&framebuffer.region) else {
+        return;
+    };
+
+    if !polygon.is_drawable() {
+        return;
+    }
+
+    let min = [0.0, 0.0];
+    let max = [framebuffer.width as f32, framebuffer.height as f32];
+
+    let orientation = signed_area(&polygon.vertices).signum();
+
+    let contours: Vec<Vec<ClipVertex>> = polygon
+        .rings()
+        .enumerate()
+        .map(|(index, ring)| {
+            let mut vertices: Vec<ClipVertex> = ring
+                .iter()
+                .map(|vertex| {
+                    let mut varyings: Vec<f32> = vertex.color.iter().map(|&c| c as f32).collect();
+                    varyings.push(vertex.depth);
+
+                    ClipVertex {
+                        position: vertex.position,
+                        varyings,
+                    }
+                })
+                .collect();
+
+            if index > 0 && signed_area(ring).signum() == orientation {
+                vertices.reverse();
+            }
+
+            clip_polygon(&vertices, min, max)
+        })
+        .collect();
+
+    fill_polygon(&contours, polygon.fill_rule, framebuffer, &region, settings, &mut |framebuffer, x, y, varyings, coverage| {
+        if settings.depth_test && !framebuffer.depth_test(x, y, varyings[DEPTH]) {
+            return;
+        }
+
+        let color = [
+            varyings[COLOR],
+            varyings[COLOR + 1],
+            varyings[COLOR + 2],
+            varyings[COLOR + 3] * coverage,
+        ];
+        framebuffer.blend_pixel(x, y, color.map(to_u8), polygon.blend_mode);
+    });
+
+    // --------------------
+    // pinta as arestas com uma cor constante caso possua, na frente do
+    // vértice mais próximo
+    // --------------------
+
+    if let Some(edges_color) = polygon.edges_color {
+        let depth = offset_depth(polygon.rings().flatten().map(|vertex| vertex.depth).fold(f32::NEG_INFINITY, f32::max));
+
+        for ring in polygon.rings() {
+            let points: Vec<[f32; 2]> = ring.iter().map(|vertex| vertex.position).collect();
+            draw_outline(&points, edges_color, polygon.blend_mode, framebuffer, &region, settings, |_, _| depth);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[[f32; 2]], fill_rule: FillRule) -> Polygon {
+        Polygon {
+            vertices: points
+                .iter()
+                .map(|&position| Vertex::new(position, [255, 255, 255, 255]))
+                .collect(),
+            holes: Vec::new(),
+            edges_color: None,
+            blend_mode: BlendMode::Normal,
+            fill_rule,
+        }
+    }
+
+    fn painted(polygon: &Polygon, x: usize, y: usize) -> bool {
+        let mut framebuffer = Framebuffer::new(40, 40);
+        render_polygon(polygon, &mut framebuffer, &RenderSettings::default());
+        framebuffer.pixel(x, y)[3] > 0
+    }
+
+    #[test]
+    fn concave_polygon_leaves_its_notch_empty() {
+        // um "U": o entalhe do meio fica fora nas duas regras
+        let points = [[5.0, 5.0], [35.0, 5.0], [35.0, 35.0], [25.0, 35.0], [25.0, 15.0], [15.0, 15.0], [15.0, 35.0], [5.0, 35.0]];
+
+        for fill_rule in FillRule::ALL {
+            let polygon = polygon(&points, fill_rule);
+            assert!(painted(&polygon, 10, 30), "{}", fill_rule.name());
+            assert!(painted(&polygon, 30, 30), "{}", fill_rule.name());
+            assert!(painted(&polygon, 20, 10), "{}", fill_rule.name());
+            assert!(!painted(&polygon, 20, 30), "{}", fill_rule.name());
+            assert!(!polygon.contains((20.5, 30.5)));
+        }
+    }
+
+    #[test]
+    fn pentagram_center_depends_on_the_fill_rule() {
+        let points: Vec<[f32; 2]> = (0..5)
+            .map(|i| {
+                let angle = std::f32::consts::FRAC_PI_2 + i as f32 * 4.0 * std::f32::consts::PI / 5.0;
+                [20.0 + 18.0 * angle.cos(), 20.0 + 18.0 * angle.sin()]
+            })
+            .collect();
+
+        let even_odd = polygon(&points, FillRule::EvenOdd);
+        let non_zero = polygon(&points, FillRule::NonZero);
+
+        // o pentágono central é envolvido duas vezes pelo contorno
+        assert_eq!(even_odd.winding_number((20.5, 20.5)).abs(), 2);
+        assert!(!painted(&even_odd, 20, 20));
+        assert!(painted(&non_zero, 20, 20));
+        assert!(!even_odd.contains((20.5, 20.5)));
+        assert!(non_zero.contains((20.5, 20.5)));
+
+        // as pontas da estrela são envolvidas uma vez
+        assert!(painted(&even_odd, 20, 35));
+        assert!(painted(&non_zero, 20, 35));
+    }
+
+    #[test]
+    fn holes_stay_empty_whatever_their_orientation() {
+        let outer = [[5.0, 5.0], [35.0, 5.0], [35.0, 35.0], [5.0, 35.0]];
+        let hole = [[15.0, 15.0], [25.0, 15.0], [25.0, 25.0], [15.0, 25.0]];
+
+        // o furo desenhado nos dois sentidos, inclusive no mesmo do contorno externo
+        for reversed in [false, true] {
+            for fill_rule in FillRule::ALL {
+                let mut points = hole;
+                if reversed {
+                    points.reverse();
+                }
+
+                let hole = polygon(&points, fill_rule).vertices;
+                let mut polygon = polygon(&outer, fill_rule);
+                polygon.holes.push(hole);
+
+                assert!(painted(&polygon, 10, 10), "{}", fill_rule.name());
+                assert!(painted(&polygon, 30, 20), "{}", fill_rule.name());
+                assert!(!painted(&polygon, 20, 20), "{}", fill_rule.name());
+                assert_eq!(polygon.winding_number((20.5, 20.5)), 0);
+                assert!(!polygon.contains((20.5, 20.5)));
+                assert!(polygon.contains((10.5, 10.5)));
+            }
+        }
+    }
+}
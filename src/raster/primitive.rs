@@ -0,0 +1,92 @@
+use super::{
+    polygon::render_polygon_region,
+    Framebuffer,
+    PixelRect,
+    Polygon,
+    Rasterizer,
+    RenderSettings,
+    Triangle,
+};
+
+// --------------------
+// algo que pode ser distribuído entre os ladrilhos e pintado em uma
+// região. os triângulos usam o rasterizador escolhido; os polígonos,
+// sempre a scanline com tabela de arestas.
+// --------------------
+
+pub trait Primitive: Sync {
+    fn bounds(&self, width: usize, height: usize) -> Option<PixelRect>;
+
+    fn render_region(
+        &self,
+        rasterizer: &dyn Rasterizer,
+        framebuffer: &mut Framebuffer,
+        region: &PixelRect,
+        settings: &RenderSettings,
+    );
+}
+
+impl Primitive for Triangle {
+    fn bounds(&self, width: usize, height: usize) -> Option<PixelRect> {
+        Triangle::bounds(self, width, height)
+    }
+
+    fn render_region(
+        &self,
+        rasterizer: &dyn Rasterizer,
+        framebuffer: &mut Framebuffer,
+        region: &PixelRect,
+        settings: &RenderSettings,
+    ) {
+        rasterizer.render_region(self, framebuffer, region, settings);
+    }
+}
+
+impl Primitive for Polygon {
+    fn bounds(&self, width: usize, height: usize) -> Option<PixelRect> {
+        Polygon::bounds(self, width, height)
+    }
+
+    fn render_region(
+        &self,
+        _rasterizer: &dyn Rasterizer,
+        framebuffer: &mut Framebuffer,
+        region: &PixelRect,
+        settings: &RenderSettings,
+    ) {
+        render_polygon_region(self, framebuffer, region, settings);
+    }
+}
+
+
+// --------------------
+// cena com triângulos e polígonos misturados, pintados na ordem
+// --------------------
+
+#[derive(Debug, Clone)]
+pub enum Shape {
+    Triangle(Triangle),
+    Polygon(Polygon),
+}
+
+impl Primitive for Shape {
+    fn bounds(&self, width: usize, height: usize) -> Option<PixelRect> {
+        match self {
+            Shape::Triangle(triangle) => triangle.bounds(width, height),
+            Shape::Polygon(polygon) => polygon.bounds(width, height),
+        }
+    }
+
+    fn render_region(
+        &self,
+        rasterizer: &dyn Rasterizer,
+        framebuffer: &mut Framebuffer,
+        region: &PixelRect,
+        settings: &RenderSettings,
+    ) {
+        match self {
+            Shape::Triangle(triangle) => Primitive::render_region(triangle, rasterizer, framebuffer, region, settings),
+            Shape::Polygon(polygon) => render_polygon_region(polygon, framebuffer, region, settings),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use super::{
+    EdgeFunction,
+    Framebuffer,
+    LineAlgorithm,
+    PixelRect,
+    PointLight,
+    Scanline,
+    Triangle,
+};
+
+// --------------------
+// algoritmo de rasterização de triângulos. todas as implementações
+// seguem a mesma convenção de centros de pixel e a regra top-left, de
+// modo que podem ser trocadas em tempo de execução e comparadas.
+// --------------------
+
+pub trait Rasterizer: Sync {
+    fn name(&self) -> &'static str;
+
+    fn render_region(
+        &self,
+        triangle: &Triangle,
+        framebuffer: &mut Framebuffer,
+        region: &PixelRect,
+        settings: &RenderSettings,
+    );
+
+    fn render(&self, triangle: &Triangle, framebuffer: &mut Framebuffer, settings: &RenderSettings) {
+        let region = framebuffer.region;
+        self.render_region(triangle, framebuffer, &region, settings);
+    }
+}
+
+
+// --------------------
+// opções que valem para a cena inteira
+// --------------------
+
+pub const ANTIALIASING_SAMPLES: usize = 4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderSettings {
+    pub antialiasing: bool,
+    pub line_algorithm: LineAlgorithm,
+    // com o Z-buffer, a profundidade dos vértices decide o que fica na
+    // frente; sem ele, vale a ordem de pintura (algoritmo do pintor)
+    pub depth_test: bool,
+    // luz pontual da cena; sem ela, as cores não são iluminadas
+    pub light: Option<PointLight>,
+}
+
+impl RenderSettings {
+    // amostras por pixel em cada eixo
+    pub fn samples(&self) -> usize {
+        if self.antialiasing { ANTIALIASING_SAMPLES } else { 1 }
+    }
+}
+
+
+pub fn rasterizers() -> &'static [&'static dyn Rasterizer] {
+    &[&Scanline, &EdgeFunction]
+}
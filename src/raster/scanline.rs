@@ -0,0 +1,822 @@
+use super::{
+    clip::{
+        clip_polygon,
+        ClipVertex,
+    },
+    fragment::{
+        shade,
+        vertex_varyings,
+    },
+    line::draw_edges,
+    FillRule,
+    Framebuffer,
+    PixelRect,
+    Rasterizer,
+    RenderSettings,
+    Triangle,
+};
+
+// --------------------
+// aresta da tabela de arestas. guarda o x e os valores interpolados
+// (varyings) na linha atual e quanto eles variam a cada linha percorrida.
+// winding é +1 se a aresta sobe no contorno original e -1 se desce.
+// --------------------
+
+#[derive(Debug, Clone)]
+struct Edge {
+    winding: i32,
+    y_end: i64,
+    x: f32,
+    dx: f32,
+    varyings: Vec<f32>,
+    dvaryings: Vec<f32>,
+}
+
+
+// --------------------
+// rasterizador por linhas de varredura (o padrão)
+// --------------------
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Scanline;
+
+impl Rasterizer for Scanline {
+    fn name(&self) -> &'static str {
+        "Scanline"
+    }
+
+    fn render_region(
+        &self,
+        triangle: &Triangle,
+        framebuffer: &mut Framebuffer,
+        region: &PixelRect,
+        settings: &RenderSettings,
+    ) {
+        render_region(triangle, framebuffer, region, settings);
+    }
+}
+
+
+pub fn render(
+    triangle: &Triangle,
+    framebuffer: &mut Framebuffer,
+    settings: &RenderSettings,
+) {
+    let region = framebuffer.region;
+    render_region(triangle, framebuffer, &region, settings);
+}
+
+
+// --------------------
+// pinta apenas os pixels do triângulo que estão dentro de region. o
+// recorte geométrico é sempre feito contra o framebuffer inteiro, então
+// cada pixel recebe exatamente a mesma cor que receberia em render.
+// --------------------
+
+pub fn render_region(
+    triangle: &Triangle,
+    framebuffer: &mut Framebuffer,
+    region: &PixelRect,
+    settings: &RenderSettings,
+) {
+    let Some(region) = region.intersection(&framebuffer.region) else {
+        return;
+    };
+
+    if !triangle.is_drawable() {
+        return;
+    }
+
+    let min = [0.0, 0.0];
+    let max = [framebuffer.width as f32, framebuffer.height as f32];
+
+    // --------------------
+    // recorta o triângulo contra o framebuffer e pinta o interior
+    // --------------------
+
+    let vertices: Vec<ClipVertex> = triangle
+        .vertices
+        .iter()
+        .zip(vertex_varyings(triangle, settings))
+        .map(|(vertex, varyings)| ClipVertex {
+            position: vertex.position,
+            varyings,
+        })
+        .collect();
+    let clipped = clip_polygon(&vertices, min, max);
+
+    fill_polygon(std::slice::from_ref(&clipped), FillRule::EvenOdd, framebuffer, &region, settings, &mut |framebuffer, x, y, varyings, coverage| {
+        shade(triangle, settings, framebuffer, x, y, varyings, coverage);
+    });
+
+    // --------------------
+    // pinta as arestas com uma cor constante caso possua
+    // --------------------
+
+    draw_edges(triangle, framebuffer, &region, settings);
+}
+
+
+// --------------------
+// scanline com tabela de arestas (ET) e tabela de arestas ativas (AET).
+// o pixel (x, y) tem centro em (x + 0.5, y + 0.5).
+//
+// a cobertura segue a regra top-left (na orientação da tela, com y para
+// baixo): um centro de pixel exatamente sobre uma aresta só é pintado se
+// a aresta for superior ou esquerda. assim, cada aresta cobre as linhas
+// cujo centro está em (y_min, y_max] e cada intervalo cobre as colunas
+// cujo centro está em [x_esquerda, x_direita). triângulos que compartilham
+// arestas pintam cada pixel exatamente uma vez.
+//
+// o polígono pode ter vários contornos (furos), côncavos ou que se
+// cruzam: as arestas de todos entram na mesma tabela, e em cada linha os
+// intervalos pintados são os que a regra de preenchimento considera
+// dentro (ver FillRule). num polígono convexo, como um triângulo, as
+// duas regras coincidem.
+//
+// com antisserrilhamento, cada linha de pixels é dividida em samples
+// sub-linhas. em cada sub-linha, a cobertura horizontal de cada pixel é
+// calculada de forma analítica, e a soma das sub-linhas multiplica o
+// alfa interpolado dos vértices.
+//
+// cada pixel coberto é entregue a shade, com os valores interpolados e
+// a fração coberta.
+// --------------------
+
+pub(super) type Shade<'a> = dyn FnMut(&mut Framebuffer, usize, usize, &mut [f32], f32) + 'a;
+
+pub(super) fn fill_polygon(
+    contours: &[Vec<ClipVertex>],
+    fill_rule: FillRule,
+    framebuffer: &mut Framebuffer,
+    region: &PixelRect,
+    settings: &RenderSettings,
+    shade: &mut Shade,
+) {
+    let Some(count) = contours.iter().find(|vertices| vertices.len() >= 3).map(|vertices| vertices[0].varyings.len()) else {
+        return;
+    };
+
+    let samples = settings.samples();
+    let scale = samples as f32;
+
+    // --------------------
+    // monta a tabela de arestas, indexada pela primeira (sub-)linha de cada aresta
+    // --------------------
+
+    let mut y_start = i64::MAX;
+    let mut y_end = i64::MIN;
+    let mut edges: Vec<(i64, Edge)> = Vec::new();
+
+    for vertices in contours.iter().filter(|vertices| vertices.len() >= 3) {
+        for i in 0..vertices.len() {
+            let a = &vertices[i];
+            let b = &vertices[(i + 1) % vertices.len()];
+            let (v0, v1, winding) = if a.position[1] <= b.position[1] { (a, b, 1) } else { (b, a, -1) };
+
+            let first_line = (v0.position[1] * scale - 0.5).floor() as i64 + 1;
+            let last_line = (v1.position[1] * scale - 0.5).floor() as i64 + 1;
+
+            // arestas horizontais (ou que não cruzam nenhum centro de linha) não entram
+            if first_line >= last_line {
+                continue;
+            }
+
+            let dy = (v1.position[1] - v0.position[1]) * scale;
+            let dx = (v1.position[0] - v0.position[0]) / dy;
+            let dvaryings = differences(&v0.varyings, &v1.varyings, dy);
+
+            // avança da posição do vértice até o centro da primeira linha
+            let offset = first_line as f32 + 0.5 - v0.position[1] * scale;
+
+            edges.push((first_line, Edge {
+                winding,
+                y_end: last_line,
+                x: v0.position[0] + dx * offset,
+                dx,
+                varyings: advance(&v0.varyings, &dvaryings, offset),
+                dvaryings,
+            }));
+
+            y_start = y_start.min(first_line);
+            y_end = y_end.max(last_line);
+        }
+    }
+
+    // as arestas são sempre percorridas desde o início para que o x
+    // acumulado não dependa da região; só a escrita é limitada a ela
+    let y_end = y_end.min((region.max[1] * samples) as i64);
+
+    edges.sort_by_key(|(first_line, _)| *first_line);
+
+    // --------------------
+    // percorre as linhas, atualizando a tabela de arestas ativas
+    // --------------------
+
+    let mut edge_table = edges.into_iter().peekable();
+    let mut active: Vec<Edge> = Vec::new();
+    let mut coverage = CoverageRow::new(region, count);
+
+    for line in y_start..y_end {
+        while let Some((_, edge)) = edge_table.next_if(|(first_line, _)| *first_line == line) {
+            active.push(edge);
+        }
+
+        active.retain(|edge| edge.y_end > line);
+        active.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+        let y = line.div_euclid(samples as i64);
+
+        if y >= region.min[1] as i64 {
+            if samples == 1 {
+                for_each_span(&active, fill_rule, |left, right| {
+                    fill_span(y, left, right, framebuffer, region, shade);
+                });
+            } else {
+                if coverage.y != y {
+                    coverage.flush(framebuffer, shade);
+                    coverage.y = y;
+                }
+                for_each_span(&active, fill_rule, |left, right| {
+                    coverage.add_span(left, right, samples);
+                });
+            }
+        }
+
+        for edge in active.iter_mut() {
+            edge.x += edge.dx;
+            for (value, delta) in edge.varyings.iter_mut().zip(&edge.dvaryings) {
+                *value += delta;
+            }
+        }
+    }
+
+    coverage.flush(framebuffer, shade);
+}
+
+
+// --------------------
+// percorre as arestas ativas (ordenadas por x) somando o winding number
+// e chama span com as arestas que abrem e fecham cada intervalo interno
+// --------------------
+
+fn for_each_span(active: &[Edge], fill_rule: FillRule, mut span: impl FnMut(&Edge, &Edge)) {
+    let mut winding = 0;
+    let mut left: Option<&Edge> = None;
+
+    for edge in active {
+        let was_inside = fill_rule.is_inside(winding);
+        winding += edge.winding;
+
+        match (was_inside, fill_rule.is_inside(winding)) {
+            (false, true) => left = Some(edge),
+            (true, false) => {
+                if let Some(left) = left.take() {
+                    span(left, edge);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+
+// --------------------
+// variação de cada valor por unidade entre dois pontos a uma distância
+// length, e os valores depois de avançar offset unidades
+// --------------------
+
+fn differences(start: &[f32], end: &[f32], length: f32) -> Vec<f32> {
+    start.iter().zip(end).map(|(a, b)| (b - a) / length).collect()
+}
+
+fn advance(values: &[f32], deltas: &[f32], offset: f32) -> Vec<f32> {
+    values.iter().zip(deltas).map(|(value, delta)| value + delta * offset).collect()
+}
+
+
+// --------------------
+// cobertura e valores interpolados acumulados das sub-linhas de uma
+// linha de pixels
+// --------------------
+
+struct CoverageRow {
+    y: i64,
+    x_min: usize,
+    x_max: usize,
+    coverage: Vec<f32>,
+    // count valores por pixel, ponderados pela cobertura
+    values: Vec<f32>,
+    count: usize,
+}
+
+impl CoverageRow {
+    fn new(region: &PixelRect, count: usize) -> Self {
+        Self {
+            y: -1,
+            x_min: region.min[0],
+            x_max: region.max[0],
+            coverage: vec![0.0; region.width()],
+            values: vec![0.0; region.width() * count],
+            count,
+        }
+    }
+
+    // --------------------
+    // soma a fração de cada pixel coberta pelo intervalo [x_esquerda, x_direita),
+    // com os valores interpolados no centro do pixel (limitado ao intervalo)
+    // --------------------
+
+    fn add_span(&mut self, left: &Edge, right: &Edge, samples: usize) {
+        let x0 = left.x.max(self.x_min as f32);
+        let x1 = right.x.min(self.x_max as f32);
+
+        if x1 <= x0 {
+            return;
+        }
+
+        let deltas = differences(&left.varyings, &right.varyings, right.x - left.x);
+
+        for x in (x0.floor() as usize)..(x1.ceil() as usize).min(self.x_max) {
+            let overlap = x1.min(x as f32 + 1.0) - x0.max(x as f32);
+            if overlap <= 0.0 {
+                continue;
+            }
+
+            let weight = overlap / samples as f32;
+            let offset = (x as f32 + 0.5).clamp(left.x, right.x) - left.x;
+            let i = x - self.x_min;
+
+            self.coverage[i] += weight;
+            let values = &mut self.values[i * self.count..(i + 1) * self.count];
+            for ((value, start), delta) in values.iter_mut().zip(&left.varyings).zip(&deltas) {
+                *value += (start + delta * offset) * weight;
+            }
+        }
+    }
+
+    fn flush(&mut self, framebuffer: &mut Framebuffer, shade: &mut Shade) {
+        if self.y < 0 {
+            return;
+        }
+
+        for (i, coverage) in self.coverage.iter_mut().enumerate() {
+            let values = &mut self.values[i * self.count..(i + 1) * self.count];
+
+            if *coverage > 0.0 {
+                for value in values.iter_mut() {
+                    *value /= *coverage;
+                }
+                shade(framebuffer, self.x_min + i, self.y as usize, values, coverage.min(1.0));
+            }
+
+            *coverage = 0.0;
+            values.fill(0.0);
+        }
+    }
+}
+
+
+// --------------------
+// pinta os pixels cujo centro está em [x_esquerda, x_direita),
+// interpolando os valores entre os extremos do intervalo
+// --------------------
+
+fn fill_span(
+    y: i64,
+    left: &Edge,
+    right: &Edge,
+    framebuffer: &mut Framebuffer,
+    region: &PixelRect,
+    shade: &mut Shade,
+) {
+    let x_start = ((left.x - 0.5).ceil() as i64).max(region.min[0] as i64);
+    let x_end = ((right.x - 0.5).ceil() as i64).min(region.max[0] as i64);
+
+    if x_start >= x_end {
+        return;
+    }
+
+    let width = right.x - left.x;
+    let deltas = if width > 0.0 {
+        differences(&left.varyings, &right.varyings, width)
+    } else {
+        vec![0.0; left.varyings.len()]
+    };
+    let mut varyings = left.varyings.clone();
+
+    for x in x_start..x_end {
+        let offset = x as f32 + 0.5 - left.x;
+
+        for ((value, start), delta) in varyings.iter_mut().zip(&left.varyings).zip(&deltas) {
+            *value = start + delta * offset;
+        }
+
+        shade(framebuffer, x as usize, y as usize, &mut varyings, 1.0);
+    }
+}
+
+
+pub(super) fn to_u8(value: f32) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::raster::{
+        rasterizers,
+        Attribute,
+        Barycentric,
+        BlendMode,
+        ColorSpace,
+        Fragment,
+        FragmentShader,
+        Gouraud,
+        PointLight,
+        ShadingModel,
+        Texture,
+        TextureFilter,
+        TextureMapping,
+        Vertex,
+    };
+
+    // --------------------
+    // pinta cada triângulo em um framebuffer separado e conta
+    // quantas vezes cada pixel foi coberto
+    // --------------------
+
+    fn coverage(rasterizer: &dyn Rasterizer, triangles: &[Triangle], width: usize, height: usize) -> Vec<usize> {
+        let mut counts = vec![0; width * height];
+
+        for triangle in triangles {
+            let mut framebuffer = Framebuffer::new(width, height);
+            rasterizer.render(triangle, &mut framebuffer, &RenderSettings::default());
+
+            for y in 0..height {
+                for x in 0..width {
+                    if framebuffer.pixel(x, y)[3] > 0 {
+                        counts[y * width + x] += 1;
+                    }
+                }
+            }
+        }
+
+        counts
+    }
+
+    fn vertex(x: f32, y: f32) -> Vertex {
+        Vertex::new([x, y], [255, 255, 255, 255])
+    }
+
+    fn triangle(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> Triangle {
+        Triangle {
+            vertices: [vertex(a[0], a[1]), vertex(b[0], b[1]), vertex(c[0], c[1])],
+            edges_color: None,
+            blend_mode: BlendMode::Normal,
+            color_space: ColorSpace::Srgb,
+            texture: None,
+            perspective_correct: false,
+            shading: ShadingModel::Gouraud,
+            shader: Arc::new(Gouraud),
+        }
+    }
+
+    // --------------------
+    // grade de quadrados divididos em dois triângulos. os pontos internos
+    // são deslocados de forma pseudoaleatória para gerar arestas inclinadas
+    // em todas as direções; os pontos da borda ficam sobre o retângulo
+    // --------------------
+
+    fn grid(
+        columns: usize,
+        rows: usize,
+        cell: f32,
+        origin: [f32; 2],
+        jitter: f32,
+        flip_diagonals: bool,
+    ) -> Vec<Triangle> {
+        let mut seed: u32 = 12345;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            ((seed >> 16) & 0x7fff) as f32 / 0x7fff as f32 - 0.5
+        };
+
+        let mut points = vec![[0.0; 2]; (columns + 1) * (rows + 1)];
+        for j in 0..=rows {
+            for i in 0..=columns {
+                let mut x = origin[0] + i as f32 * cell;
+                let mut y = origin[1] + j as f32 * cell;
+                if i > 0 && i < columns {
+                    x += random() * jitter;
+                }
+                if j > 0 && j < rows {
+                    y += random() * jitter;
+                }
+                points[j * (columns + 1) + i] = [x, y];
+            }
+        }
+
+        let mut triangles = Vec::new();
+        for j in 0..rows {
+            for i in 0..columns {
+                let p00 = points[j * (columns + 1) + i];
+                let p10 = points[j * (columns + 1) + i + 1];
+                let p01 = points[(j + 1) * (columns + 1) + i];
+                let p11 = points[(j + 1) * (columns + 1) + i + 1];
+
+                if flip_diagonals && (i + j) % 2 == 1 {
+                    triangles.push(triangle(p00, p10, p01));
+                    triangles.push(triangle(p10, p11, p01));
+                } else {
+                    triangles.push(triangle(p00, p10, p11));
+                    triangles.push(triangle(p00, p11, p01));
+                }
+            }
+        }
+
+        triangles
+    }
+
+    fn assert_covers_rectangle_once(
+        counts: &[usize],
+        rasterizer: &dyn Rasterizer,
+        width: usize,
+        height: usize,
+        min: [f32; 2],
+        max: [f32; 2],
+    ) {
+        for y in 0..height {
+            for x in 0..width {
+                let center = (x as f32 + 0.5, y as f32 + 0.5);
+                let inside = center.0 >= min[0] && center.0 < max[0] && center.1 > min[1] && center.1 <= max[1];
+                let expected = if inside { 1 } else { 0 };
+
+                assert_eq!(
+                    counts[y * width + x],
+                    expected,
+                    "{}: pixel ({}, {}) pintado {} vez(es)",
+                    rasterizer.name(),
+                    x,
+                    y,
+                    counts[y * width + x],
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn integer_grid_has_no_gaps_or_overlaps() {
+        let triangles = grid(6, 4, 8.0, [2.0, 3.0], 0.0, false);
+        for &rasterizer in rasterizers() {
+            let counts = coverage(rasterizer, &triangles, 60, 40);
+            assert_covers_rectangle_once(&counts, rasterizer, 60, 40, [2.0, 3.0], [50.0, 35.0]);
+        }
+    }
+
+    #[test]
+    fn half_pixel_grid_has_no_gaps_or_overlaps() {
+        // vértices sobre centros de pixel exercitam os casos de empate da regra
+        let triangles = grid(5, 5, 7.0, [1.5, 2.5], 0.0, true);
+        for &rasterizer in rasterizers() {
+            let counts = coverage(rasterizer, &triangles, 50, 50);
+            assert_covers_rectangle_once(&counts, rasterizer, 50, 50, [1.5, 2.5], [36.5, 37.5]);
+        }
+    }
+
+    #[test]
+    fn jittered_grid_has_no_gaps_or_overlaps() {
+        let triangles = grid(8, 6, 9.0, [3.25, 1.75], 6.0, true);
+        for &rasterizer in rasterizers() {
+            let counts = coverage(rasterizer, &triangles, 80, 60);
+            assert_covers_rectangle_once(&counts, rasterizer, 80, 60, [3.25, 1.75], [75.25, 55.75]);
+        }
+    }
+
+    #[test]
+    fn fan_around_shared_vertex_has_no_gaps_or_overlaps() {
+        let center = [20.3, 19.7];
+        let corners = [[4.0, 4.0], [36.0, 4.0], [36.0, 36.0], [4.0, 36.0]];
+
+        let mut triangles = Vec::new();
+        for i in 0..4 {
+            let a = corners[i];
+            let b = corners[(i + 1) % 4];
+            let middle = [(a[0] + b[0]) / 2.0 + 0.37, (a[1] + b[1]) / 2.0 - 0.21];
+            let middle = if i % 2 == 0 { [middle[0], a[1]] } else { [a[0], middle[1]] };
+            triangles.push(triangle(center, a, middle));
+            triangles.push(triangle(center, middle, b));
+        }
+
+        for &rasterizer in rasterizers() {
+            let counts = coverage(rasterizer, &triangles, 40, 40);
+            assert_covers_rectangle_once(&counts, rasterizer, 40, 40, [4.0, 4.0], [36.0, 36.0]);
+        }
+    }
+
+    #[test]
+    fn depth_buffer_resolves_crossing_triangles() {
+        // dois triângulos sobrepostos cujas profundidades se cruzam em x = 20
+        let crossing = |color: [u8; 4], depth: fn(f32) -> f32| {
+            let mut triangle = triangle([-10.0, -10.0], [60.0, -10.0], [-10.0, 60.0]);
+            for vertex in triangle.vertices.iter_mut() {
+                vertex.color = color;
+                vertex.depth = depth(vertex.position[0]);
+            }
+            triangle
+        };
+        let red = crossing([255, 0, 0, 255], |x| x / 4.0);
+        let blue = crossing([0, 0, 255, 255], |x| 10.0 - x / 4.0);
+
+        for &rasterizer in rasterizers() {
+            for depth_test in [false, true] {
+                let settings = RenderSettings { depth_test, ..Default::default() };
+
+                let mut red_first = Framebuffer::new(40, 40);
+                rasterizer.render(&red, &mut red_first, &settings);
+                rasterizer.render(&blue, &mut red_first, &settings);
+
+                let mut blue_first = Framebuffer::new(40, 40);
+                rasterizer.render(&blue, &mut blue_first, &settings);
+                rasterizer.render(&red, &mut blue_first, &settings);
+
+                if depth_test {
+                    assert_eq!(red_first.pixel(5, 5), [0, 0, 255, 255], "{}", rasterizer.name());
+                    assert_eq!(red_first.pixel(30, 5), [255, 0, 0, 255], "{}", rasterizer.name());
+                    assert!(red_first.pixels == blue_first.pixels, "{}", rasterizer.name());
+                } else {
+                    assert_eq!(red_first.pixel(30, 5), [0, 0, 255, 255], "{}", rasterizer.name());
+                    assert_eq!(blue_first.pixel(5, 5), [255, 0, 0, 255], "{}", rasterizer.name());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn textured_square_shows_each_texel_in_its_quadrant() {
+        // textura 2x2: vermelho e verde embaixo, azul e branco em cima
+        let texture = Arc::new(Texture {
+            name: String::new(),
+            width: 2,
+            height: 2,
+            pixels: vec![
+                0, 0, 255, 255, 255, 255, 255, 255,
+                255, 0, 0, 255, 0, 255, 0, 255,
+            ],
+        });
+
+        let corner = |x: f32, y: f32| Vertex {
+            uv: [x / 8.0, y / 8.0],
+            ..vertex(x, y)
+        };
+        let textured = |vertices: [Vertex; 3]| Triangle {
+            vertices,
+            texture: Some(TextureMapping::new(texture.clone())),
+            ..triangle([0.0; 2], [0.0; 2], [0.0; 2])
+        };
+        let square = [
+            textured([corner(0.0, 0.0), corner(8.0, 0.0), corner(8.0, 8.0)]),
+            textured([corner(0.0, 0.0), corner(8.0, 8.0), corner(0.0, 8.0)]),
+        ];
+
+        for &rasterizer in rasterizers() {
+            let mut framebuffer = Framebuffer::new(8, 8);
+            for triangle in &square {
+                rasterizer.render(triangle, &mut framebuffer, &RenderSettings::default());
+            }
+
+            assert_eq!(framebuffer.pixel(1, 1), [255, 0, 0, 255], "{}", rasterizer.name());
+            assert_eq!(framebuffer.pixel(6, 2), [0, 255, 0, 255], "{}", rasterizer.name());
+            assert_eq!(framebuffer.pixel(2, 6), [0, 0, 255, 255], "{}", rasterizer.name());
+            assert_eq!(framebuffer.pixel(5, 5), [255, 255, 255, 255], "{}", rasterizer.name());
+        }
+
+        let mut bilinear = square[0].clone();
+        bilinear.texture = Some(TextureMapping {
+            filter: TextureFilter::Bilinear,
+            ..TextureMapping::new(texture.clone())
+        });
+        let mut framebuffer = Framebuffer::new(8, 8);
+        render(&bilinear, &mut framebuffer, &RenderSettings::default());
+        assert_eq!(framebuffer.pixel(5, 4), [151, 223, 159, 255]);
+    }
+
+    #[test]
+    fn perspective_correct_interpolation_divides_by_w() {
+        // a cor de C vale x / 16 na tela; com w = 3 em C, a cor corrigida
+        // cresce mais devagar perto de A e B (w = 1)
+        let gray = |value: u8, w: f32, position: [f32; 2]| Vertex {
+            color: [value, value, value, 255],
+            w,
+            ..vertex(position[0], position[1])
+        };
+        let mut triangle = triangle([0.0; 2], [0.0; 2], [0.0; 2]);
+        triangle.vertices = [
+            gray(0, 1.0, [0.0, -100.0]),
+            gray(0, 1.0, [0.0, 100.0]),
+            gray(255, 3.0, [16.0, 0.0]),
+        ];
+
+        for &rasterizer in rasterizers() {
+            for (perspective_correct, expected) in [(false, 135), (true, 70)] {
+                triangle.perspective_correct = perspective_correct;
+
+                let mut framebuffer = Framebuffer::new(16, 16);
+                rasterizer.render(&triangle, &mut framebuffer, &RenderSettings::default());
+
+                assert_eq!(framebuffer.pixel(8, 0)[0], expected, "{}", rasterizer.name());
+            }
+        }
+    }
+
+    #[test]
+    fn shading_models_under_a_point_light() {
+        let settings = RenderSettings {
+            light: Some(PointLight {
+                position: [16.0, 16.0, 10.0],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut triangle = triangle([0.0, 0.0], [64.0, 0.0], [0.0, 64.0]);
+
+        for &rasterizer in rasterizers() {
+            let mut shade = |shading: ShadingModel| {
+                triangle.shading = shading;
+                let mut framebuffer = Framebuffer::new(64, 64);
+                rasterizer.render(&triangle, &mut framebuffer, &settings);
+                framebuffer
+            };
+
+            // flat: a mesma cor em todo o triângulo
+            let flat = shade(ShadingModel::Flat);
+            assert_eq!(flat.pixel(4, 4), flat.pixel(40, 10), "{}", rasterizer.name());
+
+            // o brilho sob a luz só aparece quando a iluminação é por pixel
+            let gouraud = shade(ShadingModel::Gouraud);
+            let phong = shade(ShadingModel::Phong);
+            assert!(gouraud.pixel(16, 16)[0] < 200, "{}", rasterizer.name());
+            assert_eq!(phong.pixel(16, 16), [255, 255, 255, 255], "{}", rasterizer.name());
+        }
+    }
+
+    #[test]
+    fn barycentric_shader_sees_the_original_vertices_after_clipping() {
+        let mut triangle = triangle([-10.0, -10.0], [100.0, -10.0], [-10.0, 100.0]);
+        triangle.shader = Arc::new(Barycentric);
+
+        for &rasterizer in rasterizers() {
+            let mut framebuffer = Framebuffer::new(64, 64);
+            rasterizer.render(&triangle, &mut framebuffer, &RenderSettings::default());
+
+            for (x, y) in [(0, 0), (63, 0), (0, 63), (30, 20)] {
+                let b = (x as f32 + 10.5) / 110.0;
+                let c = (y as f32 + 10.5) / 110.0;
+                let expected = [1.0 - b - c, b, c].map(|weight| weight * 255.0);
+
+                let pixel = framebuffer.pixel(x, y);
+                for channel in 0..3 {
+                    let error = (pixel[channel] as f32 - expected[channel]).abs();
+                    assert!(error <= 1.0, "{} ({x}, {y}): {pixel:?}", rasterizer.name());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn custom_attributes_are_matched_by_name_and_interpolated() {
+        // pinta o atributo escolhido em tons de cinza
+        #[derive(Debug)]
+        struct Gray(&'static str);
+
+        impl FragmentShader for Gray {
+            fn name(&self) -> &'static str {
+                "Cinza"
+            }
+
+            fn shade(&self, fragment: &Fragment) -> [f32; 4] {
+                let value = fragment.attribute(self.0).unwrap_or(255.0);
+                [value, value, value, 255.0]
+            }
+        }
+
+        // a vale x e b vale 64 - y; C não tem atributos (valem 0)
+        let mut triangle = triangle([0.0, 0.0], [64.0, 0.0], [0.0, 64.0]);
+        triangle.vertices[0].attributes = vec![Attribute::new("a", 0.0), Attribute::new("b", 64.0)];
+        triangle.vertices[1].attributes = vec![Attribute::new("b", 64.0), Attribute::new("a", 64.0)];
+
+        for &rasterizer in rasterizers() {
+            for (name, expected) in [("a", 30.5), ("b", 43.5)] {
+                triangle.shader = Arc::new(Gray(name));
+                let mut framebuffer = Framebuffer::new(64, 64);
+                rasterizer.render(&triangle, &mut framebuffer, &RenderSettings::default());
+
+                let value = framebuffer.pixel(30, 20)[0] as f32;
+                assert!((value - expected).abs() <= 0.5, "{} {name}: {value}", rasterizer.name());
+            }
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    fmt::Debug,
+    sync::Arc,
+};
+
+use super::Attribute;
+
+// --------------------
+// valores de um pixel coberto entregues ao shader de fragmentos:
+// - position: centro do pixel na tela
+// - barycentric: peso de cada vértice do triângulo no pixel
+// - color: cor interpolada (já com textura e iluminação), em [0, 255]
+// - uv, normal e depth: valores interpolados dos vértices
+// - attributes: atributos próprios interpolados, na ordem de layout (os
+//   atributos do primeiro vértice); use attribute para buscar pelo nome
+// --------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fragment<'a> {
+    pub position: [f32; 2],
+    pub barycentric: [f32; 3],
+    pub color: [f32; 4],
+    pub uv: [f32; 2],
+    pub normal: [f32; 3],
+    pub depth: f32,
+    pub attributes: &'a [f32],
+    pub layout: &'a [Attribute],
+}
+
+impl Fragment<'_> {
+    pub fn attribute(&self, name: &str) -> Option<f32> {
+        let index = self.layout.iter().position(|attribute| attribute.name == name)?;
+        self.attributes.get(index).copied()
+    }
+}
+
+
+// --------------------
+// shader de fragmentos: chamado pelo rasterizador para cada pixel
+// coberto, devolve a cor RGBA de saída em [0, 255], que depois é
+// composta no framebuffer com o modo de mistura do triângulo
+// --------------------
+
+pub trait FragmentShader: Debug + Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn shade(&self, fragment: &Fragment) -> [f32; 4];
+}
+
+
+// --------------------
+// devolve a cor interpolada, sem alterações (o comportamento padrão)
+// --------------------
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Gouraud;
+
+impl FragmentShader for Gouraud {
+    fn name(&self) -> &'static str {
+        "Gouraud"
+    }
+
+    fn shade(&self, fragment: &Fragment) -> [f32; 4] {
+        fragment.color
+    }
+}
+
+
+// --------------------
+// tabuleiro de xadrez nas coordenadas de textura, com cells casas por
+// unidade de uv, alternando a cor interpolada e uma versão escurecida
+// --------------------
+
+#[derive(Debug, Clone, Copy)]
+pub struct Checkerboard {
+    pub cells: f32,
+}
+
+impl Default for Checkerboard {
+    fn default() -> Self {
+        Self { cells: 8.0 }
+    }
+}
+
+impl FragmentShader for Checkerboard {
+    fn name(&self) -> &'static str {
+        "Xadrez"
+    }
+
+    fn shade(&self, fragment: &Fragment) -> [f32; 4] {
+        let cell = |c: f32| (c * self.cells).floor() as i64;
+        let dark = (cell(fragment.uv[0]) + cell(fragment.uv[1])).rem_euclid(2) == 1;
+
+        darken(fragment.color, dark)
+    }
+}
+
+
+// --------------------
+// listras diagonais nas coordenadas de textura, com cells pares de
+// listras por unidade de uv
+// --------------------
+
+#[derive(Debug, Clone, Copy)]
+pub struct Stripes {
+    pub cells: f32,
+}
+
+impl Default for Stripes {
+    fn default() -> Self {
+        Self { cells: 8.0 }
+    }
+}
+
+impl FragmentShader for Stripes {
+    fn name(&self) -> &'static str {
+        "Listras"
+    }
+
+    fn shade(&self, fragment: &Fragment) -> [f32; 4] {
+        let stripe = ((fragment.uv[0] + fragment.uv[1]) * self.cells * 2.0).floor() as i64;
+
+        darken(fragment.color, stripe.rem_euclid(2) == 1)
+    }
+}
+
+
+// --------------------
+// visualização das coordenadas baricêntricas: o peso de cada vértice
+// vira um canal (vermelho, verde e azul)
+// --------------------
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Barycentric;
+
+impl FragmentShader for Barycentric {
+    fn name(&self) -> &'static str {
+        "Baricêntricas"
+    }
+
+    fn shade(&self, fragment: &Fragment) -> [f32; 4] {
+        let [a, b, c] = fragment.barycentric;
+        [a * 255.0, b * 255.0, c * 255.0, fragment.color[3]]
+    }
+}
+
+
+// --------------------
+// mapa de calor: o atributo com o nome dado, de range[0] a range[1], vira
+// uma cor de azul (frio) a vermelho (quente). sem o atributo, o pixel
+// fica com a cor interpolada.
+// --------------------
+
+#[derive(Debug, Clone, Copy)]
+pub struct Heatmap {
+    pub attribute: &'static str,
+    pub range: [f32; 2],
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Self {
+            attribute: "temperatura",
+            range: [0.0, 1.0],
+        }
+    }
+}
+
+impl FragmentShader for Heatmap {
+    fn name(&self) -> &'static str {
+        "Mapa de calor"
+    }
+
+    fn shade(&self, fragment: &Fragment) -> [f32; 4] {
+        let Some(value) = fragment.attribute(self.attribute) else {
+            return fragment.color;
+        };
+
+        // azul, ciano, verde, amarelo e vermelho, igualmente espaçados
+        const RAMP: [[f32; 3]; 5] = [
+            [0.0, 0.0, 255.0],
+            [0.0, 255.0, 255.0],
+            [0.0, 255.0, 0.0],
+            [255.0, 255.0, 0.0],
+            [255.0, 0.0, 0.0],
+        ];
+
+        let t = ((value - self.range[0]) / (self.range[1] - self.range[0])).clamp(0.0, 1.0);
+        let t = if t.is_nan() { 0.0 } else { t * (RAMP.len() - 1) as f32 };
+        let index = (t.floor() as usize).min(RAMP.len() - 2);
+        let (a, b, t) = (RAMP[index], RAMP[index + 1], t - index as f32);
+
+        [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t, fragment.color[3]]
+    }
+}
+
+
+fn darken(color: [f32; 4], dark: bool) -> [f32; 4] {
+    if dark {
+        [color[0] * 0.25, color[1] * 0.25, color[2] * 0.25, color[3]]
+    } else {
+        color
+    }
+}
+
+
+// --------------------
+// shaders embutidos, na ordem em que aparecem no editor
+// --------------------
+
+pub fn shaders() -> Vec<Arc<dyn FragmentShader>> {
+    vec![
+        Arc::new(Gouraud),
+        Arc::new(Checkerboard::default()),
+        Arc::new(Stripes::default()),
+        Arc::new(Barycentric),
+        Arc::new(Heatmap::default()),
+    ]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragment(uv: [f32; 2]) -> Fragment<'static> {
+        Fragment {
+            position: [0.0, 0.0],
+            barycentric: [0.2, 0.3, 0.5],
+            color: [100.0, 200.0, 50.0, 255.0],
+            uv,
+            normal: [0.0, 0.0, 1.0],
+            depth: 0.0,
+            attributes: &[],
+            layout: &[],
+        }
+    }
+
+    #[test]
+    fn built_in_shaders() {
+        let light = [100.0, 200.0, 50.0, 255.0];
+        let dark = [25.0, 50.0, 12.5, 255.0];
+
+        assert_eq!(Gouraud.shade(&fragment([0.3, 0.7])), light);
+
+        let checkerboard = Checkerboard::default();
+        assert_eq!(checkerboard.shade(&fragment([0.05, 0.05])), light);
+        assert_eq!(checkerboard.shade(&fragment([0.2, 0.05])), dark);
+        assert_eq!(checkerboard.shade(&fragment([-0.05, 0.05])), dark);
+
+        let stripes = Stripes::default();
+        assert_eq!(stripes.shade(&fragment([0.01, 0.01])), light);
+        assert_eq!(stripes.shade(&fragment([0.05, 0.05])), dark);
+
+        assert_eq!(Barycentric.shade(&fragment([0.0, 0.0])), [51.0, 76.5, 127.5, 255.0]);
+    }
+
+    #[test]
+    fn heatmap_reads_the_named_attribute() {
+        let layout = [Attribute::new("material", 3.0), Attribute::new("temperatura", 0.0)];
+        let shade = |temperature: f32| {
+            Heatmap::default().shade(&Fragment {
+                attributes: &[3.0, temperature],
+                layout: &layout,
+                ..fragment([0.0, 0.0])
+            })
+        };
+
+        assert_eq!(shade(-1.0), [0.0, 0.0, 255.0, 255.0]);
+        assert_eq!(shade(0.5), [0.0, 255.0, 0.0, 255.0]);
+        assert_eq!(shade(0.875), [255.0, 127.5, 0.0, 255.0]);
+        assert_eq!(shade(2.0), [255.0, 0.0, 0.0, 255.0]);
+
+        // sem o atributo, a cor interpolada passa direto
+        assert_eq!(Heatmap::default().shade(&fragment([0.0, 0.0])), [100.0, 200.0, 50.0, 255.0]);
+    }
+}
@@ -0,0 +1,130 @@
+// --------------------
+// modelo de sombreamento de um triângulo:
+// - flat: uma única cor (a média dos vértices), iluminada uma vez no
+//   centroide com a normal média
+// - Gouraud: a iluminação é calculada nos vértices e interpolada
+// - Phong: as normais são interpoladas e a iluminação é calculada em
+//   cada pixel
+// sem luz na cena, Gouraud e Phong apenas interpolam as cores.
+// --------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShadingModel {
+    Flat,
+    #[default]
+    Gouraud,
+    Phong,
+}
+
+impl ShadingModel {
+    pub const ALL: [ShadingModel; 3] = [ShadingModel::Flat, ShadingModel::Gouraud, ShadingModel::Phong];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShadingModel::Flat => "Flat",
+            ShadingModel::Gouraud => "Gouraud",
+            ShadingModel::Phong => "Phong",
+        }
+    }
+}
+
+
+// --------------------
+// luz pontual "2.5D": a cena fica no plano z = 0, vista de frente
+// (direção do observador (0, 0, 1)), e a luz fica em position, com z
+// sendo a altura acima da tela. o modelo de reflexão é o de Phong
+// (Blinn-Phong): ambiente + difusa + especular.
+// --------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointLight {
+    pub position: [f32; 3],
+    pub color: [u8; 3],
+    pub ambient: f32,
+    pub specular: f32,
+    pub shininess: f32,
+}
+
+impl Default for PointLight {
+    fn default() -> Self {
+        Self {
+            position: [0.0, 0.0, 300.0],
+            color: [255, 255, 255],
+            ambient: 0.2,
+            specular: 0.5,
+            shininess: 32.0,
+        }
+    }
+}
+
+impl PointLight {
+    // --------------------
+    // iluminação em um ponto da tela com a normal dada. os três
+    // primeiros valores multiplicam a cor da superfície (ambiente +
+    // difusa) e os três últimos são somados a ela (especular), em [0, 1].
+    // --------------------
+
+    pub fn illuminate(&self, point: [f32; 2], normal: [f32; 3]) -> [f32; 6] {
+        let normal = normalize(normal).unwrap_or([0.0, 0.0, 1.0]);
+        let to_light = normalize([
+            self.position[0] - point[0],
+            self.position[1] - point[1],
+            self.position[2],
+        ])
+        .unwrap_or([0.0, 0.0, 1.0]);
+
+        let diffuse = dot(normal, to_light).max(0.0);
+        let specular = if diffuse > 0.0 {
+            let half = normalize([to_light[0], to_light[1], to_light[2] + 1.0]).unwrap_or([0.0, 0.0, 1.0]);
+            self.specular * dot(normal, half).max(0.0).powf(self.shininess)
+        } else {
+            0.0
+        };
+
+        let color = self.color.map(|c| c as f32 / 255.0);
+
+        [
+            self.ambient + diffuse * color[0],
+            self.ambient + diffuse * color[1],
+            self.ambient + diffuse * color[2],
+            specular * color[0],
+            specular * color[1],
+            specular * color[2],
+        ]
+    }
+}
+
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn normalize(v: [f32; 3]) -> Option<[f32; 3]> {
+    let length = dot(v, v).sqrt();
+    if length > 0.0 && length.is_finite() {
+        Some(v.map(|c| c / length))
+    } else {
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn light_straight_above_gives_full_diffuse_and_specular() {
+        let light = PointLight {
+            position: [10.0, 20.0, 50.0],
+            ..Default::default()
+        };
+
+        let lighting = light.illuminate([10.0, 20.0], [0.0, 0.0, 1.0]);
+        assert_eq!(lighting, [1.2, 1.2, 1.2, 0.5, 0.5, 0.5]);
+
+        // uma superfície voltada para longe da luz recebe só a luz ambiente
+        let lighting = light.illuminate([10.0, 20.0], [0.0, 0.0, -1.0]);
+        assert_eq!(lighting, [0.2, 0.2, 0.2, 0.0, 0.0, 0.0]);
+    }
+}
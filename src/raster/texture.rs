@@ -0,0 +1,159 @@
+use std::{
+    fmt,
+    sync::Arc,
+};
+
+// --------------------
+// textura RGBA8, armazenada linha a linha a partir do topo, como o
+// Framebuffer. as coordenadas de textura (u, v) têm origem no canto
+// inferior esquerdo da imagem e vão até 1 no canto oposto; fora desse
+// intervalo a textura se repete.
+//
+// name guarda de onde a textura veio (o caminho do arquivo, no editor).
+// --------------------
+
+#[derive(Clone, PartialEq)]
+pub struct Texture {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl fmt::Debug for Texture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Texture")
+            .field("name", &self.name)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Texture {
+    // --------------------
+    // texel na coluna x e linha y (contada de baixo para cima)
+    // --------------------
+
+    pub fn texel(&self, x: i64, y: i64) -> [f32; 4] {
+        let x = x.rem_euclid(self.width as i64) as usize;
+        let y = y.rem_euclid(self.height as i64) as usize;
+        let index = ((self.height - y - 1) * self.width + x) * 4;
+
+        [0, 1, 2, 3].map(|channel| self.pixels[index + channel] as f32)
+    }
+
+    // --------------------
+    // cor da textura em uv, com valores em [0, 255]
+    // --------------------
+
+    pub fn sample(&self, uv: [f32; 2], filter: TextureFilter) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 || !uv.iter().all(|c| c.is_finite()) {
+            return [0.0; 4];
+        }
+
+        let x = uv[0] * self.width as f32;
+        let y = uv[1] * self.height as f32;
+
+        match filter {
+            TextureFilter::Nearest => self.texel(x.floor() as i64, y.floor() as i64),
+            TextureFilter::Bilinear => {
+                // os centros dos texels ficam em (i + 0.5, j + 0.5)
+                let x = x - 0.5;
+                let y = y - 0.5;
+                let (x0, y0) = (x.floor(), y.floor());
+                let (fx, fy) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i64, y0 as i64);
+
+                let bottom = lerp(self.texel(x0, y0), self.texel(x0 + 1, y0), fx);
+                let top = lerp(self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1), fx);
+                lerp(bottom, top, fy)
+            }
+        }
+    }
+}
+
+
+fn lerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextureFilter {
+    #[default]
+    Nearest,
+    Bilinear,
+}
+
+impl TextureFilter {
+    pub const ALL: [TextureFilter; 2] = [TextureFilter::Nearest, TextureFilter::Bilinear];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextureFilter::Nearest => "Vizinho mais próximo",
+            TextureFilter::Bilinear => "Bilinear",
+        }
+    }
+}
+
+
+// --------------------
+// textura aplicada a um triângulo. com modulate, a cor da textura é
+// multiplicada pela cor interpolada dos vértices; sem, ela a substitui.
+// --------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureMapping {
+    pub texture: Arc<Texture>,
+    pub filter: TextureFilter,
+    pub modulate: bool,
+}
+
+impl TextureMapping {
+    pub fn new(texture: Arc<Texture>) -> Self {
+        Self {
+            texture,
+            filter: TextureFilter::default(),
+            modulate: false,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // textura 2x2: vermelho e verde embaixo, azul e branco em cima
+    fn checker() -> Texture {
+        Texture {
+            name: String::new(),
+            width: 2,
+            height: 2,
+            pixels: vec![
+                0, 0, 255, 255, 255, 255, 255, 255,
+                255, 0, 0, 255, 0, 255, 0, 255,
+            ],
+        }
+    }
+
+    #[test]
+    fn nearest_picks_the_texel_under_uv() {
+        let texture = checker();
+
+        assert_eq!(texture.sample([0.25, 0.25], TextureFilter::Nearest), [255.0, 0.0, 0.0, 255.0]);
+        assert_eq!(texture.sample([0.75, 0.25], TextureFilter::Nearest), [0.0, 255.0, 0.0, 255.0]);
+        assert_eq!(texture.sample([0.25, 0.75], TextureFilter::Nearest), [0.0, 0.0, 255.0, 255.0]);
+        assert_eq!(texture.sample([1.75, -0.25], TextureFilter::Nearest), [255.0, 255.0, 255.0, 255.0]);
+    }
+
+    #[test]
+    fn bilinear_blends_neighbouring_texels() {
+        let texture = checker();
+
+        assert_eq!(texture.sample([0.25, 0.25], TextureFilter::Bilinear), [255.0, 0.0, 0.0, 255.0]);
+        assert_eq!(texture.sample([0.5, 0.25], TextureFilter::Bilinear), [127.5, 127.5, 0.0, 255.0]);
+        assert_eq!(texture.sample([0.5, 0.5], TextureFilter::Bilinear), [127.5, 127.5, 127.5, 255.0]);
+    }
+}
@@ -0,0 +1,318 @@
+use super::{
+    Framebuffer,
+    PixelRect,
+    Primitive,
+    Rasterizer,
+    RenderSettings,
+};
+
+// --------------------
+// ladrilho da tela com os índices dos triângulos (ou de outras
+// primitivas) que o cruzam, já na ordem em que devem ser pintados. cada ladrilho tem seu próprio
+// framebuffer, então ladrilhos diferentes podem ser pintados em paralelo.
+// --------------------
+
+#[derive(Debug, Clone)]
+pub struct Tile {
+    pub framebuffer: Framebuffer,
+    pub triangles: Vec<usize>,
+}
+
+impl Tile {
+    pub fn render<P: Primitive>(&mut self, rasterizer: &dyn Rasterizer, settings: &RenderSettings, triangles: &[P]) {
+        let region = self.framebuffer.region;
+
+        for &index in &self.triangles {
+            triangles[index].render_region(rasterizer, &mut self.framebuffer, &region, settings);
+        }
+    }
+}
+
+
+// --------------------
+// divide region em ladrilhos de tile_size x tile_size pixels e distribui
+// os triângulos (já ordenados para pintura) entre os ladrilhos que seus
+// retângulos envolventes cruzam. ladrilhos vazios são descartados.
+// --------------------
+
+pub fn bin_triangles<P: Primitive>(
+    triangles: &[P],
+    width: usize,
+    height: usize,
+    region: &PixelRect,
+    tile_size: usize,
+) -> Vec<Tile> {
+    let Some(region) = region.intersection(&PixelRect::new(width, height)) else {
+        return Vec::new();
+    };
+
+    let tile_size = tile_size.max(1);
+    let columns = region.width().div_ceil(tile_size);
+    let rows = region.height().div_ceil(tile_size);
+
+    let mut bins: Vec<Vec<usize>> = vec![Vec::new(); columns * rows];
+
+    for (index, triangle) in triangles.iter().enumerate() {
+        let Some(bounds) = triangle.bounds(width, height).and_then(|bounds| bounds.intersection(&region)) else {
+            continue;
+        };
+
+        let column_start = (bounds.min[0] - region.min[0]) / tile_size;
+        let column_end = (bounds.max[0] - 1 - region.min[0]) / tile_size;
+        let row_start = (bounds.min[1] - region.min[1]) / tile_size;
+        let row_end = (bounds.max[1] - 1 - region.min[1]) / tile_size;
+
+        for row in row_start..=row_end {
+            for column in column_start..=column_end {
+                bins[row * columns + column].push(index);
+            }
+        }
+    }
+
+    bins
+        .into_iter()
+        .enumerate()
+        .filter(|(_, triangles)| !triangles.is_empty())
+        .map(|(bin, triangles)| {
+            let (row, column) = (bin / columns, bin % columns);
+            let min = [
+                region.min[0] + column * tile_size,
+                region.min[1] + row * tile_size,
+            ];
+            let rect = PixelRect {
+                min,
+                max: [
+                    (min[0] + tile_size).min(region.max[0]),
+                    (min[1] + tile_size).min(region.max[1]),
+                ],
+            };
+
+            Tile {
+                framebuffer: Framebuffer::tile(width, height, rect),
+                triangles,
+            }
+        })
+        .collect()
+}
+
+
+// --------------------
+// pinta os triângulos em region ladrilho por ladrilho. quem chama escolhe
+// como os ladrilhos são distribuídos entre as threads: spawn recebe os
+// ladrilhos e a função que pinta cada um, e deve chamá-la uma vez para
+// cada ladrilho. o resultado é idêntico ao da pintura serial, já que cada
+// pixel é calculado da mesma forma nos dois caminhos.
+// --------------------
+
+pub fn render_tiled<P: Primitive>(
+    rasterizer: &dyn Rasterizer,
+    settings: &RenderSettings,
+    triangles: &[P],
+    framebuffer: &mut Framebuffer,
+    region: &PixelRect,
+    tile_size: usize,
+    spawn: impl FnOnce(&mut [Tile], &(dyn Fn(&mut Tile) + Sync)),
+) {
+    let mut tiles = bin_triangles(triangles, framebuffer.width, framebuffer.height, region, tile_size);
+
+    spawn(&mut tiles, &|tile: &mut Tile| tile.render(rasterizer, settings, triangles));
+
+    framebuffer.clear_region(region);
+    for tile in &tiles {
+        framebuffer.blit(&tile.framebuffer);
+    }
+}
+
+
+// --------------------
+// distribui os ladrilhos entre uma thread por núcleo disponível
+// --------------------
+
+pub fn spawn_threads(tiles: &mut [Tile], render: &(dyn Fn(&mut Tile) + Sync)) {
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk_size = tiles.len().div_ceil(threads).max(1);
+
+    std::thread::scope(|scope| {
+        for chunk in tiles.chunks_mut(chunk_size) {
+            scope.spawn(move || {
+                for tile in chunk {
+                    render(tile);
+                }
+            });
+        }
+    });
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::raster::{
+        rasterizers,
+        shaders,
+        Attribute,
+        BlendMode,
+        ColorSpace,
+        FillRule,
+        LineAlgorithm,
+        PointLight,
+        Polygon,
+        ShadingModel,
+        Shape,
+        Texture,
+        TextureFilter,
+        TextureMapping,
+        Triangle,
+        Vertex,
+    };
+
+    // --------------------
+    // triângulos aleatórios com todas as opções; um em cada cinco vira um
+    // quadrilátero (que pode se cruzar), pintado como polígono
+    // --------------------
+
+    fn scene() -> Vec<Shape> {
+        let mut seed: u32 = 7;
+        let mut random = move |limit: f32| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            ((seed >> 16) & 0x7fff) as f32 / 0x7fff as f32 * limit
+        };
+
+        let texture = Arc::new(Texture {
+            name: String::new(),
+            width: 5,
+            height: 3,
+            pixels: (0..5 * 3 * 4).map(|i| (i * 37 % 256) as u8).collect(),
+        });
+
+        let shaders = shaders();
+
+        (0..200)
+            .map(|i| {
+                let mut vertex = |_| Vertex {
+                    color: [
+                        random(255.0) as u8,
+                        random(255.0) as u8,
+                        random(255.0) as u8,
+                        if i % 2 == 0 { 255 } else { random(255.0) as u8 },
+                    ],
+                    position: [random(340.0) - 20.0, random(260.0) - 20.0],
+                    depth: random(100.0),
+                    uv: [random(2.0) - 0.5, random(2.0) - 0.5],
+                    w: 0.5 + random(2.5),
+                    normal: [random(2.0) - 1.0, random(2.0) - 1.0, random(1.0)],
+                    attributes: vec![Attribute::new("temperatura", random(1.0))],
+                };
+
+                let triangle = Triangle {
+                    vertices: [vertex(0), vertex(1), vertex(2)],
+                    edges_color: if i % 5 == 0 { Some([0, 0, 0]) } else { None },
+                    blend_mode: BlendMode::ALL[i % BlendMode::ALL.len()],
+                    color_space: ColorSpace::ALL[i / 2 % ColorSpace::ALL.len()],
+                    texture: if i % 3 == 0 {
+                        Some(TextureMapping {
+                            texture: texture.clone(),
+                            filter: TextureFilter::ALL[i / 3 % 2],
+                            modulate: i % 2 == 0,
+                        })
+                    } else {
+                        None
+                    },
+                    perspective_correct: i % 4 < 2,
+                    shading: ShadingModel::ALL[i % ShadingModel::ALL.len()],
+                    shader: shaders[i / 3 % shaders.len()].clone(),
+                };
+
+                if i % 5 == 2 {
+                    let [a, b, c] = triangle.vertices.clone();
+                    let d = Vertex {
+                        position: [
+                            a.position[0] + b.position[0] - c.position[0],
+                            a.position[1] + b.position[1] - c.position[1],
+                        ],
+                        ..c.clone()
+                    };
+
+                    // metade deles com um furo: o mesmo quadrilátero, reduzido à metade
+                    let vertices = vec![a, b, c, d];
+                    let center = [
+                        vertices.iter().map(|vertex| vertex.position[0]).sum::<f32>() / 4.0,
+                        vertices.iter().map(|vertex| vertex.position[1]).sum::<f32>() / 4.0,
+                    ];
+                    let hole = vertices
+                        .iter()
+                        .map(|vertex| Vertex {
+                            position: [
+                                (vertex.position[0] + center[0]) / 2.0,
+                                (vertex.position[1] + center[1]) / 2.0,
+                            ],
+                            ..vertex.clone()
+                        })
+                        .collect();
+
+                    Shape::Polygon(Polygon {
+                        vertices,
+                        holes: if i % 10 == 7 { vec![hole] } else { Vec::new() },
+                        edges_color: if i % 10 == 2 { Some([0, 0, 64]) } else { None },
+                        blend_mode: triangle.blend_mode,
+                        fill_rule: FillRule::ALL[i / 5 % 2],
+                    })
+                } else {
+                    Shape::Triangle(triangle)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tiled_output_is_identical_to_serial_output() {
+        let shapes = scene();
+
+        let light = Some(PointLight {
+            position: [150.0, 100.0, 80.0],
+            ..Default::default()
+        });
+
+        for (antialiasing, line_algorithm, depth_test, light) in [
+            (false, LineAlgorithm::Bresenham, false, None),
+            (true, LineAlgorithm::Dda, false, None),
+            (true, LineAlgorithm::XiaolinWu, false, light),
+            (false, LineAlgorithm::XiaolinWu, true, light),
+            (true, LineAlgorithm::Bresenham, true, None),
+        ] {
+            let settings = RenderSettings {
+                antialiasing,
+                line_algorithm,
+                depth_test,
+                light,
+            };
+
+            for &rasterizer in rasterizers() {
+                let mut serial = Framebuffer::new(300, 220);
+                let region = serial.region;
+                for shape in &shapes {
+                    shape.render_region(rasterizer, &mut serial, &region, &settings);
+                }
+
+                for tile_size in [13, 64, 500] {
+                    let mut tiled = Framebuffer::new(300, 220);
+                    let region = tiled.region;
+                    render_tiled(rasterizer, &settings, &shapes, &mut tiled, &region, tile_size, spawn_threads);
+
+                    assert!(
+                        tiled == serial,
+                        "{}, ladrilhos de {} pixels, antisserrilhamento {}, arestas {}, Z-buffer {}, luz {}",
+                        rasterizer.name(),
+                        tile_size,
+                        antialiasing,
+                        line_algorithm.name(),
+                        depth_test,
+                        light.is_some(),
+                    );
+                }
+            }
+        }
+    }
+}
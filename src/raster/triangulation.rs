@@ -0,0 +1,363 @@
+// --------------------
+// triangulação de um polígono simples (sem furos e sem arestas que se
+// cruzam) pelo corte de orelhas: uma orelha é um vértice convexo cujo
+// triângulo com os dois vizinhos não contém nenhum outro vértice. ela é
+// cortada, e o processo se repete até sobrar um único triângulo.
+//
+// devolve os índices dos pontos de cada triângulo, ou None se o
+// polígono tiver menos de três pontos ou se cruzar
+// --------------------
+
+pub fn ear_clipping(points: &[[f32; 2]]) -> Option<Vec<[usize; 3]>> {
+    if points.len() < 3 || !is_simple(points) {
+        return None;
+    }
+
+    // percorre os pontos sempre no sentido anti-horário
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    if signed_area(points) < 0.0 {
+        remaining.reverse();
+    }
+
+    let mut triangles = Vec::with_capacity(points.len() - 2);
+
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let corner = |i: usize| [remaining[(i + count - 1) % count], remaining[i], remaining[(i + 1) % count]];
+
+        if let Some(i) = (0..count).find(|&i| is_ear(points, &remaining, corner(i))) {
+            triangles.push(corner(i));
+            remaining.remove(i);
+        } else {
+            // sem orelhas, só sobram vértices alinhados com os vizinhos:
+            // eles são removidos sem gerar triângulos
+            let i = (0..count).find(|&i| {
+                let [a, b, c] = corner(i);
+                cross(points[a], points[b], points[c]) == 0.0
+            })?;
+            remaining.remove(i);
+        }
+    }
+
+    let [a, b, c] = [remaining[0], remaining[1], remaining[2]];
+    if cross(points[a], points[b], points[c]) != 0.0 {
+        triangles.push([a, b, c]);
+    }
+
+    Some(triangles)
+}
+
+
+fn is_ear(points: &[[f32; 2]], remaining: &[usize], [a, b, c]: [usize; 3]) -> bool {
+    let (pa, pb, pc) = (points[a], points[b], points[c]);
+
+    if cross(pa, pb, pc) <= 0.0 {
+        return false;
+    }
+
+    // nenhum outro vértice pode estar dentro ou sobre a borda do triângulo
+    remaining.iter().all(|&p| {
+        let point = points[p];
+        if p == a || p == b || p == c || point == pa || point == pb || point == pc {
+            return true;
+        }
+
+        cross(pa, pb, point) < 0.0 || cross(pb, pc, point) < 0.0 || cross(pc, pa, point) < 0.0
+    })
+}
+
+
+// --------------------
+// um polígono é simples se nenhum par de arestas não vizinhas se toca
+// --------------------
+
+fn is_simple(points: &[[f32; 2]]) -> bool {
+    let n = points.len();
+    let edge = |i: usize| (points[i], points[(i + 1) % n]);
+
+    for i in 0..n {
+        for j in i + 2..n {
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+
+            let (a, b) = edge(i);
+            let (c, d) = edge(j);
+            if segments_intersect(a, b, c, d) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn segments_intersect(a: [f32; 2], b: [f32; 2], c: [f32; 2], d: [f32; 2]) -> bool {
+    let d1 = cross(c, d, a);
+    let d2 = cross(c, d, b);
+    let d3 = cross(a, b, c);
+    let d4 = cross(a, b, d);
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0)) && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0)) {
+        return true;
+    }
+
+    (d1 == 0.0 && on_segment(c, d, a))
+        || (d2 == 0.0 && on_segment(c, d, b))
+        || (d3 == 0.0 && on_segment(a, b, c))
+        || (d4 == 0.0 && on_segment(a, b, d))
+}
+
+// p, já alinhado com a e b, está entre eles
+fn on_segment(a: [f32; 2], b: [f32; 2], p: [f32; 2]) -> bool {
+    p[0] >= a[0].min(b[0]) && p[0] <= a[0].max(b[0]) && p[1] >= a[1].min(b[1]) && p[1] <= a[1].max(b[1])
+}
+
+
+// positivo se a, b e c estão no sentido anti-horário
+fn cross(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+fn signed_area(points: &[[f32; 2]]) -> f32 {
+    let mut area = 0.0;
+
+    for i in 0..points.len() {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        area += a[0] * b[1] - b[0] * a[1];
+    }
+
+    area / 2.0
+}
+
+
+// --------------------
+// triangulação de Delaunay de uma nuvem de pontos pelo algoritmo de
+// Bowyer-Watson: os pontos são inseridos um a um em uma triangulação
+// que começa com um triângulo enorme em volta de todos eles. cada ponto
+// apaga os triângulos cujo círculo circunscrito o contém, e o buraco
+// que fica é ligado ao ponto. no fim, os triângulos que usam os vértices
+// do triângulo inicial são descartados.
+//
+// o triângulo inicial é simbólico: seus vértices ficam em s * SUPER,
+// com s tendendo ao infinito. com um s finito, por maior que fosse,
+// uma nuvem fina o bastante teria círculos circunscritos que alcançam
+// esses vértices, e arestas do fecho convexo se perderiam.
+//
+// devolve os índices dos pontos de cada triângulo, no sentido
+// anti-horário. pontos repetidos são ignorados.
+// --------------------
+
+pub fn delaunay(points: &[[f32; 2]]) -> Vec<[usize; 3]> {
+    let n = points.len();
+    if n < 3 {
+        return Vec::new();
+    }
+
+    // as contas são feitas em f64 para que o teste do círculo não erre
+    // com pontos quase alinhados
+    let mut vertices: Vec<Symbolic> = points.iter().map(|p| real([p[0] as f64, p[1] as f64])).collect();
+    vertices.extend(SUPER.map(|[x, y]| [[0.0, x], [0.0, y]]));
+
+    let mut triangles: Vec<[usize; 3]> = vec![[n, n + 1, n + 2]];
+
+    for p in 0..n {
+        let point = vertices[p];
+        if vertices[..p].contains(&point) {
+            continue;
+        }
+
+        let (bad, good): (Vec<[usize; 3]>, Vec<[usize; 3]>) = triangles
+            .into_iter()
+            .partition(|&[a, b, c]| in_circle(vertices[a], vertices[b], vertices[c], point));
+        triangles = good;
+
+        // arestas da borda do buraco: as que pertencem a um só triângulo apagado
+        for (i, triangle) in bad.iter().enumerate() {
+            for k in 0..3 {
+                let edge = (triangle[k], triangle[(k + 1) % 3]);
+                let shared = bad.iter().enumerate().any(|(j, other)| {
+                    j != i && (0..3).any(|l| (other[(l + 1) % 3], other[l]) == edge)
+                });
+
+                if !shared {
+                    triangles.push([edge.0, edge.1, p]);
+                }
+            }
+        }
+    }
+
+    triangles.retain(|triangle| triangle.iter().all(|&index| index < n));
+    triangles
+}
+
+// direções dos vértices do triângulo inicial, no sentido anti-horário
+const SUPER: [[f64; 2]; 3] = [[-1.0, -1.0], [1.0, -1.0], [0.0, 1.0]];
+
+// coordenadas de um vértice como polinômios em s: [constante, coeficiente de s]
+type Symbolic = [[f64; 2]; 2];
+
+// polinômio em s, do termo constante ao de grau 4
+type Polynomial = [f64; 5];
+
+fn real([x, y]: [f64; 2]) -> Symbolic {
+    [[x, 0.0], [y, 0.0]]
+}
+
+fn product(p: &[f64], q: &[f64]) -> Polynomial {
+    let mut result = [0.0; 5];
+    for (i, p) in p.iter().enumerate() {
+        for (j, q) in q.iter().enumerate() {
+            if i + j < 5 {
+                result[i + j] += p * q;
+            }
+        }
+    }
+    result
+}
+
+// d está dentro do círculo que passa por a, b e c (no sentido
+// anti-horário). o determinante é um polinômio em s, e o sinal dele
+// com s infinito é o do termo não nulo de maior grau.
+fn in_circle(a: Symbolic, b: Symbolic, c: Symbolic, d: Symbolic) -> bool {
+    let relative = |p: Symbolic| [0, 1].map(|axis| [p[axis][0] - d[axis][0], p[axis][1] - d[axis][1]]);
+    let [a, b, c] = [a, b, c].map(relative);
+
+    let lift = |[x, y]: [[f64; 2]; 2]| {
+        let [x, y] = [product(&x, &x), product(&y, &y)];
+        [0, 1, 2, 3, 4].map(|i| x[i] + y[i])
+    };
+    let cross = |[px, py]: [[f64; 2]; 2], [qx, qy]: [[f64; 2]; 2]| {
+        let [first, second] = [product(&px, &qy), product(&qx, &py)];
+        [0, 1, 2, 3, 4].map(|i| first[i] - second[i])
+    };
+
+    let terms = [
+        product(&lift(a), &cross(b, c)),
+        product(&lift(b), &cross(a, c)),
+        product(&lift(c), &cross(a, b)),
+    ];
+    let determinant: Polynomial = [0, 1, 2, 3, 4].map(|i| terms[0][i] - terms[1][i] + terms[2][i]);
+
+    determinant.iter().rev().find(|&&coefficient| coefficient != 0.0).is_some_and(|&coefficient| coefficient > 0.0)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(points: &[[f32; 2]], triangles: &[[usize; 3]]) -> f32 {
+        triangles
+            .iter()
+            .map(|&[a, b, c]| cross(points[a], points[b], points[c]).abs() / 2.0)
+            .sum()
+    }
+
+    #[test]
+    fn concave_polygon_is_covered_exactly_once() {
+        // um "U" desenhado no sentido horário
+        let mut points = vec![[5.0, 5.0], [35.0, 5.0], [35.0, 35.0], [25.0, 35.0], [25.0, 15.0], [15.0, 15.0], [15.0, 35.0], [5.0, 35.0]];
+        points.reverse();
+
+        let triangles = ear_clipping(&points).unwrap();
+
+        assert_eq!(triangles.len(), points.len() - 2);
+        assert_eq!(area(&points, &triangles), signed_area(&points).abs());
+
+        // nenhum triângulo cobre o entalhe
+        for &[a, b, c] in &triangles {
+            let center = [
+                (points[a][0] + points[b][0] + points[c][0]) / 3.0,
+                (points[a][1] + points[b][1] + points[c][1]) / 3.0,
+            ];
+            assert!(!(center[0] > 15.0 && center[0] < 25.0 && center[1] > 15.0), "{:?}", center);
+        }
+    }
+
+    #[test]
+    fn aligned_points_do_not_produce_empty_triangles() {
+        let points = [[0.0, 0.0], [10.0, 0.0], [20.0, 0.0], [20.0, 10.0], [0.0, 10.0]];
+        let triangles = ear_clipping(&points).unwrap();
+
+        assert_eq!(area(&points, &triangles), 200.0);
+        assert!(triangles.iter().all(|&[a, b, c]| cross(points[a], points[b], points[c]) != 0.0));
+    }
+
+    #[test]
+    fn crossing_polygons_are_rejected() {
+        assert!(ear_clipping(&[[0.0, 0.0], [10.0, 10.0], [10.0, 0.0], [0.0, 10.0]]).is_none());
+        assert!(ear_clipping(&[[0.0, 0.0], [10.0, 0.0]]).is_none());
+    }
+
+    #[test]
+    fn delaunay_circles_are_empty() {
+        // uma grade irregular de pontos, com um repetido
+        let mut points: Vec<[f32; 2]> = (0..30)
+            .map(|i| [(i % 6) as f32 * 20.0 + (i * 7 % 5) as f32, (i / 6) as f32 * 20.0 + (i * 3 % 4) as f32])
+            .collect();
+        points.push(points[7]);
+
+        let triangles = delaunay(&points);
+        let as_f64 = |p: [f32; 2]| real([p[0] as f64, p[1] as f64]);
+
+        for &[a, b, c] in &triangles {
+            assert!(cross(points[a], points[b], points[c]) > 0.0);
+
+            for (p, &point) in points.iter().enumerate() {
+                if p != a && p != b && p != c && point != points[a] && point != points[b] && point != points[c] {
+                    assert!(!in_circle(as_f64(points[a]), as_f64(points[b]), as_f64(points[c]), as_f64(point)));
+                }
+            }
+        }
+
+        // cada ponto distinto aparece em pelo menos um triângulo
+        for p in 0..30 {
+            assert!(triangles.iter().any(|triangle| triangle.contains(&p)), "{}", p);
+        }
+    }
+
+    #[test]
+    fn delaunay_of_a_square_covers_it() {
+        let points = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [5.0, 5.0]];
+        let triangles = delaunay(&points);
+
+        assert_eq!(triangles.len(), 4);
+        assert_eq!(area(&points, &triangles), 100.0);
+        assert!(delaunay(&[[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]]).is_empty());
+    }
+
+    #[test]
+    fn thin_cloud_keeps_every_hull_edge() {
+        // pontos quase alinhados: 300 de largura e 0.02 de altura. com um
+        // triângulo inicial 20 ou 1000 vezes maior que a nuvem, várias
+        // arestas do fecho convexo sumiam
+        let points: Vec<[f32; 2]> = (0..31)
+            .map(|i| [i as f32 * 10.0 + (i * i % 7) as f32 * 0.3, 100.0 + 0.01 * (i as f32 * 2.3).sin()])
+            .collect();
+
+        let triangles = delaunay(&points);
+
+        // as arestas do fecho no sentido anti-horário são as que deixam
+        // todos os outros pontos à esquerda
+        let left = |a: [f32; 2], b: [f32; 2], p: [f32; 2]| {
+            let [a, b, p] = [a, b, p].map(|p| [p[0] as f64, p[1] as f64]);
+            (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0]) > 0.0
+        };
+        let mut edges = 0;
+        for a in 0..points.len() {
+            for b in 0..points.len() {
+                let on_hull = a != b
+                    && (0..points.len()).all(|p| p == a || p == b || left(points[a], points[b], points[p]));
+
+                if on_hull {
+                    edges += 1;
+                    let present = triangles.iter().any(|triangle| (0..3).any(|k| [triangle[k], triangle[(k + 1) % 3]] == [a, b]));
+                    assert!(present, "aresta {:?} do fecho convexo", [a, b]);
+                }
+            }
+        }
+        assert!(edges > 3);
+    }
+}
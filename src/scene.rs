@@ -0,0 +1,662 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+};
+
+use bevy::prelude::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value;
+
+use cg_scanline::raster::{
+    self,
+    Attribute,
+    BlendMode,
+    ColorSpace,
+    FillRule,
+    LineAlgorithm,
+    PointLight,
+    Rasterizer,
+    RenderSettings,
+    ShadingModel,
+    TextureFilter,
+    TextureMapping,
+};
+
+use crate::{
+    canvas::{
+        load_texture,
+        Canvas,
+    },
+    polygons::Polygon,
+    shared_vertices::VertexPool,
+    state::State,
+    triangles::{
+        generated_texture,
+        is_generated,
+        Triangle,
+        Vertex,
+    },
+};
+
+// --------------------
+// arquivo de cena em JSON. version muda sempre que um campo muda de
+// significado ou deixa de existir; campos novos e opcionais não mudam a
+// versão, desde que tenham #[serde(default)]. os enums são guardados
+// pelos nomes mostrados na interface.
+// --------------------
+
+pub const SCENE_VERSION: u64 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SceneFile {
+    version: u64,
+    rasterizer: String,
+    settings: SettingsData,
+    triangles_count: usize,
+    #[serde(default)]
+    vertex_pool: Vec<VertexData>,
+    triangles: Vec<TriangleData>,
+    #[serde(default)]
+    polygons: Vec<PolygonData>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SettingsData {
+    antialiasing: bool,
+    line_algorithm: String,
+    depth_test: bool,
+    light: Option<LightData>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LightData {
+    position: [f32; 3],
+    color: [u8; 3],
+    ambient: f32,
+    specular: f32,
+    shininess: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VertexData {
+    position: [f32; 2],
+    color: [u8; 4],
+    depth: f32,
+    uv: [f32; 2],
+    w: f32,
+    normal: [f32; 3],
+    #[serde(default)]
+    attributes: Vec<AttributeData>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AttributeData {
+    name: String,
+    value: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TriangleData {
+    index: usize,
+    vertices: [VertexData; 3],
+    edges_color: Option<[u8; 3]>,
+    blend_mode: String,
+    color_space: String,
+    texture: Option<TextureData>,
+    perspective_correct: bool,
+    shading: String,
+    shader: String,
+    #[serde(default)]
+    shared: [Option<usize>; 3],
+}
+
+// a textura é guardada pelo caminho do arquivo e recarregada na abertura,
+// ou, se generated, pelo nome com que ela é gerada de novo
+#[derive(Debug, Serialize, Deserialize)]
+struct TextureData {
+    path: String,
+    #[serde(default)]
+    generated: bool,
+    filter: String,
+    modulate: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PolygonData {
+    index: usize,
+    vertices: Vec<VertexData>,
+    #[serde(default)]
+    holes: Vec<Vec<VertexData>>,
+    edges_color: Option<[u8; 3]>,
+    blend_mode: String,
+    fill_rule: String,
+}
+
+
+// --------------------
+// cena carregada, pronta para substituir a atual
+// --------------------
+
+pub struct Scene {
+    pub rasterizer: &'static dyn Rasterizer,
+    pub settings: RenderSettings,
+    pub triangles_count: usize,
+    pub vertex_pool: Vec<Vertex>,
+    pub triangles: Vec<Triangle>,
+    pub polygons: Vec<Polygon>,
+    // texturas que não puderam ser recarregadas
+    pub missing_textures: Vec<String>,
+}
+
+impl Scene {
+    // --------------------
+    // substitui a cena atual pela carregada de uma vez só, com acesso
+    // exclusivo ao mundo: nenhum sistema chega a ver os triângulos antigos
+    // com os índices do pool novo
+    // --------------------
+
+    pub fn replace(self, world: &mut World) {
+        let shapes: Vec<Entity> = world
+            .query_filtered::<Entity, Or<(With<Triangle>, With<Polygon>)>>()
+            .iter(world)
+            .collect();
+        for entity in shapes {
+            world.despawn(entity);
+        }
+
+        world.spawn_batch(self.triangles);
+        world.spawn_batch(self.polygons);
+        world.resource_mut::<VertexPool>().vertices = self.vertex_pool;
+
+        let mut canvas = world.resource_mut::<Canvas>();
+        canvas.rasterizer = self.rasterizer;
+        canvas.settings = self.settings;
+
+        world.resource_mut::<State>().triangles_count = self.triangles_count;
+    }
+}
+
+
+pub fn save_scene<'a>(
+    path: &str,
+    rasterizer: &dyn Rasterizer,
+    settings: &RenderSettings,
+    triangles_count: usize,
+    vertex_pool: &[Vertex],
+    triangles: impl Iterator<Item = &'a Triangle>,
+    polygons: impl Iterator<Item = &'a Polygon>,
+) -> Result<(), String> {
+    let json = scene_to_json(rasterizer, settings, triangles_count, vertex_pool, triangles, polygons)?;
+    std::fs::write(path, json).map_err(|error| error.to_string())
+}
+
+pub fn load_scene(path: &str) -> Result<Scene, String> {
+    let json = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+    scene_from_json(&json)
+}
+
+
+fn scene_to_json<'a>(
+    rasterizer: &dyn Rasterizer,
+    settings: &RenderSettings,
+    triangles_count: usize,
+    vertex_pool: &[Vertex],
+    triangles: impl Iterator<Item = &'a Triangle>,
+    polygons: impl Iterator<Item = &'a Polygon>,
+) -> Result<String, String> {
+    // as formas são guardadas na ordem de pintura
+    let mut triangles: Vec<TriangleData> = triangles.map(triangle_data).collect();
+    triangles.sort_by_key(|triangle| triangle.index);
+    let mut polygons: Vec<PolygonData> = polygons.map(polygon_data).collect();
+    polygons.sort_by_key(|polygon| polygon.index);
+
+    let file = SceneFile {
+        version: SCENE_VERSION,
+        rasterizer: rasterizer.name().to_string(),
+        settings: SettingsData {
+            antialiasing: settings.antialiasing,
+            line_algorithm: settings.line_algorithm.name().to_string(),
+            depth_test: settings.depth_test,
+            light: settings.light.map(|light| LightData {
+                position: light.position,
+                color: light.color,
+                ambient: light.ambient,
+                specular: light.specular,
+                shininess: light.shininess,
+            }),
+        },
+        triangles_count,
+        vertex_pool: vertex_pool.iter().map(vertex_data).collect(),
+        triangles,
+        polygons,
+    };
+
+    serde_json::to_string_pretty(&file).map_err(|error| error.to_string())
+}
+
+fn scene_from_json(json: &str) -> Result<Scene, String> {
+    let value: Value = serde_json::from_str(json).map_err(|error| error.to_string())?;
+    let file: SceneFile = serde_json::from_value(migrate(value)?).map_err(|error| error.to_string())?;
+
+    let rasterizer = *raster::rasterizers()
+        .iter()
+        .find(|rasterizer| rasterizer.name() == file.rasterizer)
+        .ok_or_else(|| unknown("rasterizador", &file.rasterizer))?;
+
+    let settings = RenderSettings {
+        antialiasing: file.settings.antialiasing,
+        line_algorithm: by_name("linhas das arestas", &LineAlgorithm::ALL, LineAlgorithm::name, &file.settings.line_algorithm)?,
+        depth_test: file.settings.depth_test,
+        light: file.settings.light.map(|light| PointLight {
+            position: light.position,
+            color: light.color,
+            ambient: light.ambient,
+            specular: light.specular,
+            shininess: light.shininess,
+        }),
+    };
+
+    let pool_size = file.vertex_pool.len();
+    let mut missing_textures = Vec::new();
+
+    // cada textura é carregada uma vez só e dividida entre os triângulos
+    let mut textures: HashMap<(bool, String), Option<Arc<raster::Texture>>> = HashMap::new();
+
+    let triangles = file
+        .triangles
+        .into_iter()
+        .map(|triangle| {
+            if triangle.shared.iter().flatten().any(|&shared| shared >= pool_size) {
+                return Err(format!("triângulo {} usa um vértice compartilhado inexistente", triangle.index));
+            }
+
+            let texture = match triangle.texture {
+                Some(texture) => {
+                    let loaded = textures
+                        .entry((texture.generated, texture.path.clone()))
+                        .or_insert_with(|| {
+                            let loaded = if texture.generated {
+                                generated_texture(&texture.path)
+                            } else {
+                                load_texture(&texture.path).ok()
+                            };
+                            if loaded.is_none() {
+                                missing_textures.push(texture.path.clone());
+                            }
+                            loaded.map(Arc::new)
+                        })
+                        .clone();
+
+                    match loaded {
+                        Some(loaded) => Some(TextureMapping {
+                            texture: loaded,
+                            filter: by_name("filtro", &TextureFilter::ALL, TextureFilter::name, &texture.filter)?,
+                            modulate: texture.modulate,
+                        }),
+                        None => None,
+                    }
+                }
+                None => None,
+            };
+
+            let shader = raster::shaders()
+                .into_iter()
+                .find(|shader| shader.name() == triangle.shader)
+                .ok_or_else(|| unknown("shader", &triangle.shader))?;
+
+            let [first, middle, last] = triangle.vertices.map(vertex);
+
+            Ok(Triangle {
+                first,
+                middle,
+                last,
+                redraw: true,
+                index: triangle.index,
+                edges_color: triangle.edges_color,
+                blend_mode: by_name("mistura", &BlendMode::ALL, BlendMode::name, &triangle.blend_mode)?,
+                color_space: by_name("interpolação", &ColorSpace::ALL, ColorSpace::name, &triangle.color_space)?,
+                texture,
+                perspective_correct: triangle.perspective_correct,
+                shading: by_name("sombreamento", &ShadingModel::ALL, ShadingModel::name, &triangle.shading)?,
+                shader,
+                shared: triangle.shared,
+            })
+        })
+        .collect::<Result<Vec<Triangle>, String>>()?;
+
+    let polygons = file
+        .polygons
+        .into_iter()
+        .map(|polygon| {
+            Ok(Polygon {
+                vertices: polygon.vertices.into_iter().map(vertex).collect(),
+                holes: polygon.holes.into_iter().map(|hole| hole.into_iter().map(vertex).collect()).collect(),
+                redraw: true,
+                index: polygon.index,
+                edges_color: polygon.edges_color,
+                blend_mode: by_name("mistura", &BlendMode::ALL, BlendMode::name, &polygon.blend_mode)?,
+                fill_rule: by_name("preenchimento", &FillRule::ALL, FillRule::name, &polygon.fill_rule)?,
+            })
+        })
+        .collect::<Result<Vec<Polygon>, String>>()?;
+
+    Ok(Scene {
+        rasterizer,
+        settings,
+        triangles_count: file.triangles_count,
+        vertex_pool: file.vertex_pool.into_iter().map(vertex).collect(),
+        triangles,
+        polygons,
+        missing_textures,
+    })
+}
+
+
+// --------------------
+// converte um arquivo de uma versão anterior para a atual. cada versão
+// nova ganha aqui um passo que transforma o JSON da versão anterior.
+// --------------------
+
+fn migrate(value: Value) -> Result<Value, String> {
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| String::from("o arquivo não informa a versão da cena"))?;
+
+    match version {
+        SCENE_VERSION => Ok(value),
+        version if version > SCENE_VERSION => Err(format!(
+            "a cena é da versão {}, mais nova que a suportada ({})",
+            version, SCENE_VERSION,
+        )),
+        version => Err(format!("versão {} da cena desconhecida", version)),
+    }
+}
+
+
+fn by_name<T: Copy>(what: &str, all: &[T], name_of: fn(&T) -> &'static str, name: &str) -> Result<T, String> {
+    all.iter()
+        .copied()
+        .find(|item| name_of(item) == name)
+        .ok_or_else(|| unknown(what, name))
+}
+
+fn unknown(what: &str, name: &str) -> String {
+    format!("{}: valor desconhecido \"{}\"", what, name)
+}
+
+
+fn vertex_data(vertex: &Vertex) -> VertexData {
+    VertexData {
+        position: vertex.position,
+        color: vertex.color,
+        depth: vertex.depth,
+        uv: vertex.uv,
+        w: vertex.w,
+        normal: vertex.normal,
+        attributes: vertex
+            .attributes
+            .iter()
+            .map(|attribute| AttributeData {
+                name: attribute.name.clone(),
+                value: attribute.value,
+            })
+            .collect(),
+    }
+}
+
+fn vertex(data: VertexData) -> Vertex {
+    Vertex {
+        position: data.position,
+        color: data.color,
+        depth: data.depth,
+        uv: data.uv,
+        w: data.w,
+        normal: data.normal,
+        attributes: data
+            .attributes
+            .into_iter()
+            .map(|attribute| Attribute::new(attribute.name, attribute.value))
+            .collect(),
+    }
+}
+
+fn triangle_data(triangle: &Triangle) -> TriangleData {
+    TriangleData {
+        index: triangle.index,
+        vertices: triangle.corners().map(vertex_data),
+        edges_color: triangle.edges_color,
+        blend_mode: triangle.blend_mode.name().to_string(),
+        color_space: triangle.color_space.name().to_string(),
+        texture: triangle.texture.as_ref().map(|mapping| TextureData {
+            path: mapping.texture.name.clone(),
+            generated: is_generated(&mapping.texture),
+            filter: mapping.filter.name().to_string(),
+            modulate: mapping.modulate,
+        }),
+        perspective_correct: triangle.perspective_correct,
+        shading: triangle.shading.name().to_string(),
+        shader: triangle.shader.name().to_string(),
+        shared: triangle.shared,
+    }
+}
+
+fn polygon_data(polygon: &Polygon) -> PolygonData {
+    PolygonData {
+        index: polygon.index,
+        vertices: polygon.vertices.iter().map(vertex_data).collect(),
+        holes: polygon.holes.iter().map(|hole| hole.iter().map(vertex_data).collect()).collect(),
+        edges_color: polygon.edges_color,
+        blend_mode: polygon.blend_mode.name().to_string(),
+        fill_rule: polygon.fill_rule.name().to_string(),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::AssetPlugin;
+
+    use super::*;
+    use crate::{
+        canvas::CanvasPlugin,
+        shared_vertices::SharedVerticesPlugin,
+        state::StatePlugin,
+        triangles::{
+            redrawing,
+            tilted_quads,
+        },
+    };
+
+    fn vertex(x: f32, y: f32) -> Vertex {
+        Vertex {
+            depth: 0.5,
+            uv: [0.25, 0.75],
+            w: 2.0,
+            attributes: vec![Attribute::new("temperatura", 0.5)],
+            ..Vertex::new([x, y], [10, 20, 30, 255])
+        }
+    }
+
+    #[test]
+    fn scene_survives_a_round_trip() {
+        let triangle = Triangle {
+            redraw: false,
+            index: 3,
+            edges_color: Some([1, 2, 3]),
+            blend_mode: BlendMode::ALL[2],
+            color_space: ColorSpace::ALL[1],
+            perspective_correct: false,
+            shading: ShadingModel::Phong,
+            shared: [Some(0), None, None],
+            ..Triangle::test(vertex(10.0, 10.0), vertex(100.0, 10.0), vertex(10.0, 100.0))
+        };
+        let polygon = Polygon {
+            vertices: vec![vertex(0.0, 0.0), vertex(50.0, 0.0), vertex(50.0, 50.0)],
+            holes: vec![vec![vertex(30.0, 10.0), vertex(40.0, 10.0), vertex(40.0, 20.0)]],
+            redraw: false,
+            index: 1,
+            edges_color: None,
+            blend_mode: BlendMode::Normal,
+            fill_rule: FillRule::NonZero,
+        };
+        let settings = RenderSettings {
+            antialiasing: true,
+            line_algorithm: LineAlgorithm::ALL[2],
+            depth_test: true,
+            light: Some(PointLight::default()),
+        };
+        let rasterizer = raster::rasterizers()[1];
+
+        let json = scene_to_json(rasterizer, &settings, 4, &[vertex(10.0, 10.0)], [&triangle].into_iter(), [&polygon].into_iter()).unwrap();
+        assert!(json.contains("\"version\": 1"));
+
+        let scene = scene_from_json(&json).unwrap();
+
+        assert_eq!(scene.rasterizer.name(), rasterizer.name());
+        assert_eq!(scene.settings.antialiasing, settings.antialiasing);
+        assert_eq!(scene.settings.line_algorithm, settings.line_algorithm);
+        assert_eq!(scene.settings.light.map(|light| light.position), settings.light.map(|light| light.position));
+        assert_eq!(scene.triangles_count, 4);
+        assert_eq!(scene.vertex_pool, vec![vertex(10.0, 10.0)]);
+
+        let loaded = &scene.triangles[0];
+        assert_eq!(loaded.corners(), triangle.corners());
+        assert_eq!(loaded.index, 3);
+        assert_eq!(loaded.edges_color, Some([1, 2, 3]));
+        assert_eq!(loaded.blend_mode, triangle.blend_mode);
+        assert_eq!(loaded.color_space, triangle.color_space);
+        assert_eq!(loaded.shading, ShadingModel::Phong);
+        assert_eq!(loaded.shader.name(), "Gouraud");
+        assert_eq!(loaded.shared, [Some(0), None, None]);
+        assert!(loaded.redraw);
+
+        let loaded = &scene.polygons[0];
+        assert_eq!(loaded.vertices, polygon.vertices);
+        assert_eq!(loaded.holes, polygon.holes);
+        assert_eq!(loaded.fill_rule, FillRule::NonZero);
+    }
+
+    #[test]
+    fn generated_textures_are_rebuilt_and_shared() {
+        let triangles = tilted_quads(1);
+        let rasterizer = raster::rasterizers()[0];
+
+        let json = scene_to_json(rasterizer, &RenderSettings::default(), 5, &[], triangles.iter(), std::iter::empty()).unwrap();
+        let scene = scene_from_json(&json).unwrap();
+
+        assert!(scene.missing_textures.is_empty());
+        assert_eq!(scene.triangles.len(), triangles.len());
+
+        let original = &triangles[0].texture.as_ref().unwrap().texture;
+        let loaded: Vec<&Arc<raster::Texture>> = scene
+            .triangles
+            .iter()
+            .map(|triangle| &triangle.texture.as_ref().unwrap().texture)
+            .collect();
+        assert_eq!(loaded[0].pixels, original.pixels);
+        assert!(loaded.iter().all(|texture| Arc::ptr_eq(texture, loaded[0])));
+
+        // sem a marca, o nome é um caminho de arquivo como outro qualquer
+        assert!(json.contains("\"generated\": true"));
+        let json = json.replace("\"generated\": true", "\"generated\": false");
+        let scene = scene_from_json(&json).unwrap();
+
+        assert_eq!(scene.missing_textures, vec![original.name.clone()]);
+        assert!(scene.triangles.iter().all(|triangle| triangle.texture.is_none()));
+    }
+
+    #[test]
+    fn unknown_names_say_which_field_is_wrong() {
+        let triangles = tilted_quads(1);
+        let json = scene_to_json(raster::rasterizers()[0], &RenderSettings::default(), 5, &[], triangles.iter(), std::iter::empty()).unwrap();
+
+        for (field, error) in [
+            ("blend_mode", "mistura: valor desconhecido \"?\""),
+            ("color_space", "interpolação: valor desconhecido \"?\""),
+            ("shading", "sombreamento: valor desconhecido \"?\""),
+        ] {
+            let mut value: Value = serde_json::from_str(&json).unwrap();
+            value["triangles"][0][field] = Value::from("?");
+
+            assert_eq!(scene_from_json(&value.to_string()).err().as_deref(), Some(error));
+        }
+    }
+
+    #[test]
+    fn missing_textures_are_listed_once() {
+        let mut triangles = tilted_quads(1);
+        for triangle in triangles.iter_mut() {
+            let mapping = triangle.texture.as_mut().unwrap();
+            mapping.texture = Arc::new(raster::Texture {
+                name: String::from("nao/existe.png"),
+                ..(*mapping.texture).clone()
+            });
+        }
+        let rasterizer = raster::rasterizers()[0];
+
+        let json = scene_to_json(rasterizer, &RenderSettings::default(), 5, &[], triangles.iter(), std::iter::empty()).unwrap();
+        let scene = scene_from_json(&json).unwrap();
+
+        assert_eq!(scene.missing_textures, vec![String::from("nao/existe.png")]);
+        assert!(scene.triangles.iter().all(|triangle| triangle.texture.is_none()));
+    }
+
+    #[test]
+    fn loading_replaces_the_shapes_and_the_pool_together() {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(AssetPlugin::default())
+            .init_asset::<Image>()
+            .add_plugins(CanvasPlugin)
+            .add_plugins(StatePlugin)
+            .add_plugins(SharedVerticesPlugin)
+            .add_systems(Update, redrawing);
+        app.update();
+
+        let triangle = |shared| Triangle {
+            shared,
+            ..Triangle::test(vertex(10.0, 10.0), vertex(100.0, 10.0), vertex(10.0, 100.0))
+        };
+
+        // cena atual: um triângulo que usa o último de seis vértices do pool
+        let old = app.world.spawn(triangle([None, None, Some(5)])).id();
+        app.world.resource_mut::<VertexPool>().vertices = vec![vertex(10.0, 100.0); 6];
+        app.update();
+
+        let new = triangle([Some(0), None, None]);
+        let json = scene_to_json(raster::rasterizers()[1], &RenderSettings::default(), 7, &[vertex(10.0, 10.0)], [&new].into_iter(), std::iter::empty()).unwrap();
+        let scene = scene_from_json(&json).unwrap();
+
+        // o triângulo antigo é editado no mesmo quadro em que a cena é trocada
+        app.world.get_mut::<Triangle>(old).unwrap().last.position = [20.0, 120.0];
+        scene.replace(&mut app.world);
+        app.update();
+
+        assert!(app.world.get_entity(old).is_none());
+        let triangles: Vec<&Triangle> = app.world.query::<&Triangle>().iter(&app.world).collect();
+        assert_eq!(triangles.len(), 1);
+        assert_eq!(triangles[0].shared, [Some(0), None, None]);
+        assert_eq!(app.world.resource::<VertexPool>().vertices, vec![vertex(10.0, 10.0)]);
+        assert_eq!(app.world.resource::<State>().triangles_count, 7);
+        assert_eq!(app.world.resource::<Canvas>().rasterizer.name(), raster::rasterizers()[1].name());
+    }
+
+    #[test]
+    fn newer_or_unversioned_scenes_are_rejected() {
+        let scene = |version: &str| {
+            format!(
+                r#"{{ {} "rasterizer": "Scanline", "triangles_count": 1, "triangles": [],
+                    "settings": {{ "antialiasing": false, "line_algorithm": "Bresenham", "depth_test": false, "light": null }} }}"#,
+                version,
+            )
+        };
+
+        assert!(scene_from_json(&scene("\"version\": 2,")).err().unwrap().contains("mais nova"));
+        assert!(scene_from_json(&scene("")).err().unwrap().contains("versão"));
+    }
+}
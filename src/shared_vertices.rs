@@ -0,0 +1,354 @@
+use bevy::prelude::*;
+
+use crate::triangles::{
+    modifying,
+    redrawing,
+    Triangle,
+    Vertex,
+};
+
+pub struct SharedVerticesPlugin;
+
+impl Plugin for SharedVerticesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<VertexPool>()
+            .add_systems(Update, sharing.after(modifying).before(redrawing))
+            .add_systems(PostUpdate, compacting);
+    }
+}
+
+// --------------------
+// malha indexada: os vértices soldados ficam no VertexPool, e cada
+// triângulo guarda em shared o índice no pool de cada um dos seus
+// vértices (None quando o vértice é só dele). os triângulos continuam
+// com cópias dos vértices, usadas na pintura, que o sistema sharing
+// mantém iguais às do pool.
+// --------------------
+
+#[derive(Debug, Default, Resource)]
+pub struct VertexPool {
+    pub vertices: Vec<Vertex>,
+}
+
+impl VertexPool {
+    pub fn add(&mut self, vertex: Vertex) -> usize {
+        self.vertices.push(vertex);
+        self.vertices.len() - 1
+    }
+
+    // --------------------
+    // descarta os vértices que não estão em uso e devolve, para cada
+    // índice antigo, o novo índice (None para os descartados)
+    // --------------------
+
+    pub fn compact(&mut self, used: &[bool]) -> Vec<Option<usize>> {
+        let mut next = 0;
+        let remap: Vec<Option<usize>> = used
+            .iter()
+            .map(|&used| {
+                used.then(|| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect();
+
+        let mut index = 0;
+        self.vertices.retain(|_| {
+            index += 1;
+            used[index - 1]
+        });
+
+        remap
+    }
+}
+
+// distância máxima, em pixels, entre vértices soldados
+const WELD_DISTANCE: f32 = 8.0;
+
+
+// --------------------
+// quando o vértice compartilhado de um triângulo é editado (movido,
+// pintado ou alterado na janela de propriedades), a edição vai para o
+// pool e dele para todos os outros triângulos que usam o mesmo vértice
+// --------------------
+
+pub fn sharing(
+    mut vertex_pool: ResMut<VertexPool>,
+    mut triangles_query: Query<&mut Triangle>,
+) {
+    let mut edited: Vec<usize> = Vec::new();
+
+    for triangle in triangles_query.iter_mut() {
+        if !triangle.is_changed() {
+            continue;
+        }
+
+        for (vertex, shared) in triangle.corners().into_iter().zip(triangle.shared) {
+            if let Some(shared) = shared {
+                if vertex_pool.vertices[shared] != *vertex && !edited.contains(&shared) {
+                    vertex_pool.vertices[shared] = vertex.clone();
+                    edited.push(shared);
+                }
+            }
+        }
+    }
+
+    if edited.is_empty() {
+        return;
+    }
+
+    for mut triangle in triangles_query.iter_mut() {
+        let outdated = triangle
+            .corners()
+            .into_iter()
+            .zip(triangle.shared)
+            .any(|(vertex, shared)| shared.is_some_and(|shared| vertex_pool.vertices[shared] != *vertex));
+
+        if outdated {
+            let shared = triangle.shared;
+            for (vertex, shared) in triangle.corners_mut().into_iter().zip(shared) {
+                if let Some(shared) = shared {
+                    *vertex = vertex_pool.vertices[shared].clone();
+                }
+            }
+            triangle.redraw = true;
+        }
+    }
+}
+
+
+// --------------------
+// os vértices do pool que nenhum triângulo usa mais (de triângulos
+// removidos ou desoldados, ou de grupos absorvidos por uma solda) são
+// descartados, e os índices dos triângulos, renumerados. roda no
+// PostUpdate, depois que os triângulos criados no Update já existem.
+// --------------------
+
+pub fn compacting(
+    mut vertex_pool: ResMut<VertexPool>,
+    mut triangles_query: Query<&mut Triangle>,
+) {
+    let mut used = vec![false; vertex_pool.vertices.len()];
+    for triangle in triangles_query.iter() {
+        for shared in triangle.shared.into_iter().flatten() {
+            if let Some(used) = used.get_mut(shared) {
+                *used = true;
+            }
+        }
+    }
+
+    if used.iter().all(|&used| used) {
+        return;
+    }
+
+    let remap = vertex_pool.compact(&used);
+
+    for mut triangle in triangles_query.iter_mut() {
+        let shared = triangle.shared.map(|shared| shared.and_then(|shared| remap.get(shared).copied().flatten()));
+        if triangle.shared != shared {
+            triangle.shared = shared;
+        }
+    }
+}
+
+
+// --------------------
+// solda cada vértice do triângulo selecionado aos vértices dos outros
+// triângulos que estejam a no máximo WELD_DISTANCE pixels dele. os
+// vértices soldados passam a ser o do triângulo selecionado, e os grupos
+// que já existiam são unidos ao dele.
+// --------------------
+
+pub fn weld(vertex_pool: &mut VertexPool, triangles_query: &mut Query<(Entity, &mut Triangle)>, entity: Entity) {
+    let Ok((_, mut selected)) = triangles_query.get_mut(entity) else {
+        return;
+    };
+
+    let mut groups = [0; 3];
+    let shared = selected.shared;
+    for (k, vertex) in selected.corners().into_iter().enumerate() {
+        groups[k] = match shared[k] {
+            Some(index) => index,
+            None => vertex_pool.add(vertex.clone()),
+        };
+    }
+    selected.shared = groups.map(Some);
+
+    let positions = selected.corners().map(|vertex| vertex.position);
+
+    // grupos antigos que foram absorvidos, com o grupo que os absorveu
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+
+    for (other, mut triangle) in triangles_query.iter_mut() {
+        if other == entity {
+            continue;
+        }
+
+        let shared = triangle.shared;
+        for (m, old) in shared.into_iter().enumerate() {
+            let position = triangle.corners()[m].position;
+            let near = positions.iter().position(|selected| {
+                (position[0] - selected[0]).abs() < WELD_DISTANCE && (position[1] - selected[1]).abs() < WELD_DISTANCE
+            });
+
+            if let Some(k) = near {
+                if let Some(old) = old.filter(|old| !groups.contains(old)) {
+                    merged.push((old, groups[k]));
+                }
+
+                triangle.shared[m] = Some(groups[k]);
+                *triangle.corners_mut()[m] = vertex_pool.vertices[groups[k]].clone();
+                triangle.redraw = true;
+            }
+        }
+    }
+
+    if merged.is_empty() {
+        return;
+    }
+
+    for (_, mut triangle) in triangles_query.iter_mut() {
+        for m in 0..3 {
+            let group = triangle.shared[m].and_then(|old| merged.iter().find(|(from, _)| *from == old));
+
+            if let Some(&(_, group)) = group {
+                triangle.shared[m] = Some(group);
+                *triangle.corners_mut()[m] = vertex_pool.vertices[group].clone();
+                triangle.redraw = true;
+            }
+        }
+    }
+}
+
+
+// separa os vértices do triângulo dos vértices compartilhados: as cópias
+// continuam no lugar, mas deixam de acompanhar as edições dos outros
+pub fn unweld(triangles_query: &mut Query<(Entity, &mut Triangle)>, entity: Entity) {
+    if let Ok((_, mut triangle)) = triangles_query.get_mut(entity) {
+        triangle.shared = [None; 3];
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn triangle(index: usize, positions: [[f32; 2]; 3]) -> Triangle {
+        let [first, middle, last] = positions.map(|position| Vertex::new(position, [255, 255, 255, 255]));
+
+        Triangle {
+            redraw: false,
+            index,
+            ..Triangle::test(first, middle, last)
+        }
+    }
+
+    #[test]
+    fn welded_vertices_follow_each_other_until_unwelded() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_plugins(SharedVerticesPlugin);
+
+        // dois triângulos com uma aresta quase em comum, e um longe deles
+        let a = app.world.spawn(triangle(1, [[10.0, 10.0], [100.0, 10.0], [10.0, 100.0]])).id();
+        let b = app.world.spawn(triangle(2, [[102.0, 12.0], [100.0, 100.0], [12.0, 98.0]])).id();
+        let c = app.world.spawn(triangle(3, [[300.0, 300.0], [400.0, 300.0], [300.0, 400.0]])).id();
+        app.update();
+
+        app.world.run_system_once(move |mut vertex_pool: ResMut<VertexPool>, mut triangles_query: Query<(Entity, &mut Triangle)>| {
+            weld(&mut vertex_pool, &mut triangles_query, a);
+        });
+        app.update();
+
+        {
+            let b = app.world.get::<Triangle>(b).unwrap();
+            assert_eq!(b.first.position, [100.0, 10.0]);
+            assert_eq!(b.last.position, [10.0, 100.0]);
+            assert_eq!(b.middle.position, [100.0, 100.0]);
+            assert!(b.shared[0].is_some() && b.shared[1].is_none());
+            assert_eq!(app.world.get::<Triangle>(c).unwrap().shared, [None; 3]);
+        }
+
+        // mover e pintar o vértice em um triângulo atualiza o outro
+        {
+            let mut a = app.world.get_mut::<Triangle>(a).unwrap();
+            a.middle.position = [120.0, 20.0];
+            a.middle.color = [255, 0, 0, 255];
+            a.redraw = true;
+        }
+        app.update();
+
+        {
+            let b = app.world.get::<Triangle>(b).unwrap();
+            assert_eq!(b.first.position, [120.0, 20.0]);
+            assert_eq!(b.first.color, [255, 0, 0, 255]);
+            assert!(b.redraw);
+        }
+
+        // depois de desoldado, o vértice de b não acompanha mais o de a
+        app.world.run_system_once(move |mut triangles_query: Query<(Entity, &mut Triangle)>| {
+            unweld(&mut triangles_query, b);
+        });
+        app.world.get_mut::<Triangle>(a).unwrap().middle.position = [140.0, 30.0];
+        app.update();
+
+        assert_eq!(app.world.get::<Triangle>(b).unwrap().first.position, [120.0, 20.0]);
+    }
+
+    #[test]
+    fn unused_pool_vertices_are_dropped() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_plugins(SharedVerticesPlugin);
+
+        // c é soldado primeiro, então os vértices de a e b vêm depois dos dele no pool
+        let a = app.world.spawn(triangle(1, [[10.0, 10.0], [100.0, 10.0], [10.0, 100.0]])).id();
+        let b = app.world.spawn(triangle(1, [[102.0, 12.0], [100.0, 100.0], [12.0, 98.0]])).id();
+        let c = app.world.spawn(triangle(3, [[300.0, 300.0], [400.0, 300.0], [300.0, 400.0]])).id();
+        app.update();
+
+        for entity in [c, a] {
+            app.world.run_system_once(move |mut vertex_pool: ResMut<VertexPool>, mut triangles_query: Query<(Entity, &mut Triangle)>| {
+                weld(&mut vertex_pool, &mut triangles_query, entity);
+            });
+        }
+        app.update();
+        assert_eq!(app.world.resource::<VertexPool>().vertices.len(), 6);
+
+        // a e b têm o mesmo índice, mas só os vértices de b perto de a são soldados
+        {
+            let b = app.world.get::<Triangle>(b).unwrap();
+            assert!(b.shared[0].is_some() && b.shared[1].is_none() && b.shared[2].is_some());
+        }
+
+        app.world.despawn(c);
+        app.update();
+
+        let pool = &app.world.resource::<VertexPool>().vertices;
+        assert_eq!(pool.len(), 3);
+        for entity in [a, b] {
+            let triangle = app.world.get::<Triangle>(entity).unwrap();
+            for (vertex, shared) in triangle.corners().into_iter().zip(triangle.shared) {
+                if let Some(shared) = shared {
+                    assert_eq!(pool[shared], *vertex);
+                }
+            }
+        }
+
+        // desoldar b não libera nada, já que a ainda usa os três vértices;
+        // desoldar a também esvazia o pool
+        let unweld_triangle = |app: &mut App, entity: Entity| {
+            app.world.run_system_once(move |mut triangles_query: Query<(Entity, &mut Triangle)>| {
+                unweld(&mut triangles_query, entity);
+            });
+            app.update();
+            app.world.resource::<VertexPool>().vertices.len()
+        };
+
+        assert_eq!(unweld_triangle(&mut app, b), 3);
+        assert_eq!(unweld_triangle(&mut app, a), 0);
+    }
+}
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use cg_scanline::raster::FillRule;
+
 use crate::triangles::{
     Vertex,
     VertexOrder,
@@ -17,21 +19,41 @@ impl Plugin for StatePlugin {
 pub enum Function {
     None,
     Create,
+    CreatePolygon,
     Select,
     Modify(Entity),
+    ModifyPolygon(Entity),
+    CreateHole(Entity),
+    CreateDelaunay,
 }
 
 #[derive(Resource)]
 pub struct State {
     pub function: Function,
     pub selected_vertex: Option<VertexOrder>,
-    pub vertex_color_picker: [u8; 3],
+    pub vertex_color_picker: [u8; 4],
     pub edges_color_picker: [u8; 3],
     pub constant_edges: bool,
     pub new_triangle: Vec<Vertex>,
+    pub draw_polygon: bool,
+    pub triangulation_message: Option<String>,
+    pub point_cloud: Vec<Vertex>,
+    pub points_path: String,
+    pub points_message: Option<String>,
+    pub new_polygon: Vec<Vertex>,
+    pub fill_rule: FillRule,
     pub spawn_vertex_selectors: bool,
     pub triangles_count: usize,
     pub show_properties_window: bool,
+    pub export_scale: u32,
+    pub export_path: String,
+    pub export_message: Option<String>,
+    pub scene_path: String,
+    pub scene_message: Option<String>,
+    pub rasterizer_comparison: Vec<String>,
+    pub texture_path: String,
+    pub texture_message: Option<String>,
+    pub attribute_name_string: String,
 
     pub edges_color_r_string: String,
     pub edges_color_g_string: String,
@@ -40,23 +62,47 @@ pub struct State {
 
     pub first_position_x_string: String,
     pub first_position_y_string: String,
+    pub first_depth_string: String,
+    pub first_uv_u_string: String,
+    pub first_uv_v_string: String,
+    pub first_w_string: String,
+    pub first_normal_x_string: String,
+    pub first_normal_y_string: String,
+    pub first_normal_z_string: String,
     pub first_color_r_string: String,
     pub first_color_g_string: String,
     pub first_color_b_string: String,
+    pub first_color_a_string: String,
     pub first_vertex_string_parsing_error: bool,
 
     pub middle_position_x_string: String,
     pub middle_position_y_string: String,
+    pub middle_depth_string: String,
+    pub middle_uv_u_string: String,
+    pub middle_uv_v_string: String,
+    pub middle_w_string: String,
+    pub middle_normal_x_string: String,
+    pub middle_normal_y_string: String,
+    pub middle_normal_z_string: String,
     pub middle_color_r_string: String,
     pub middle_color_g_string: String,
     pub middle_color_b_string: String,
+    pub middle_color_a_string: String,
     pub middle_vertex_string_parsing_error: bool,
 
     pub last_position_x_string: String,
     pub last_position_y_string: String,
+    pub last_depth_string: String,
+    pub last_uv_u_string: String,
+    pub last_uv_v_string: String,
+    pub last_w_string: String,
+    pub last_normal_x_string: String,
+    pub last_normal_y_string: String,
+    pub last_normal_z_string: String,
     pub last_color_r_string: String,
     pub last_color_g_string: String,
     pub last_color_b_string: String,
+    pub last_color_a_string: String,
     pub last_vertex_string_parsing_error: bool,
 }
 
@@ -65,15 +111,31 @@ fn setup_state(
 ) {
     commands.insert_resource(State {
         function: Function::None,
-        vertex_color_picker: [255, 0, 0],
+        vertex_color_picker: [255, 0, 0, 255],
         edges_color_picker: [0, 0, 0],
         constant_edges: false,
         new_triangle: Vec::new(),
+        draw_polygon: false,
+        triangulation_message: None,
+        point_cloud: Vec::new(),
+        points_path: String::from("pontos.txt"),
+        points_message: None,
+        new_polygon: Vec::new(),
+        fill_rule: FillRule::EvenOdd,
         spawn_vertex_selectors: false,
         selected_vertex: None,
         triangles_count: 1,
 
         show_properties_window: false,
+        export_scale: 1,
+        export_path: String::from("cena.png"),
+        export_message: None,
+        scene_path: String::from("cena.json"),
+        scene_message: None,
+        rasterizer_comparison: Vec::new(),
+        texture_path: String::from("textura.png"),
+        texture_message: None,
+        attribute_name_string: String::from("temperatura"),
 
         edges_color_r_string: String::new(),
         edges_color_g_string: String::new(),
@@ -82,23 +144,47 @@ fn setup_state(
 
         first_position_x_string: String::new(),
         first_position_y_string: String::new(),
+        first_depth_string: String::new(),
+        first_uv_u_string: String::new(),
+        first_uv_v_string: String::new(),
+        first_w_string: String::new(),
+        first_normal_x_string: String::new(),
+        first_normal_y_string: String::new(),
+        first_normal_z_string: String::new(),
         first_color_r_string: String::new(),
         first_color_g_string: String::new(),
         first_color_b_string: String::new(),
+        first_color_a_string: String::new(),
         first_vertex_string_parsing_error: false,
 
         middle_position_x_string: String::new(),
         middle_position_y_string: String::new(),
+        middle_depth_string: String::new(),
+        middle_uv_u_string: String::new(),
+        middle_uv_v_string: String::new(),
+        middle_w_string: String::new(),
+        middle_normal_x_string: String::new(),
+        middle_normal_y_string: String::new(),
+        middle_normal_z_string: String::new(),
         middle_color_r_string: String::new(),
         middle_color_g_string: String::new(),
         middle_color_b_string: String::new(),
+        middle_color_a_string: String::new(),
         middle_vertex_string_parsing_error: false,
 
         last_position_x_string: String::new(),
         last_position_y_string: String::new(),
+        last_depth_string: String::new(),
+        last_uv_u_string: String::new(),
+        last_uv_v_string: String::new(),
+        last_w_string: String::new(),
+        last_normal_x_string: String::new(),
+        last_normal_y_string: String::new(),
+        last_normal_z_string: String::new(),
         last_color_r_string: String::new(),
         last_color_g_string: String::new(),
         last_color_b_string: String::new(),
+        last_color_a_string: String::new(),
         last_vertex_string_parsing_error: false,
     });
 }
\ No newline at end of file
@@ -1,15 +1,50 @@
+use std::{
+    sync::Arc,
+    time::Instant,
+};
+
 use bevy::{
     prelude::*,
-    render::render_resource::*,
     window::PrimaryWindow,
 };
 use bevy_egui::EguiContexts;
 
-use crate::state::{
-    Function,
-    State,
+use cg_scanline::raster::{
+    self,
+    BlendMode,
+    ColorSpace,
+    FragmentShader,
+    Gouraud,
+    PixelRect,
+    ShadingModel,
+    Texture,
+    TextureMapping,
+};
+
+use crate::{
+    canvas::{
+        merge_rects,
+        render_parallel,
+        Canvas,
+    },
+    constants::{
+        HEIGHT,
+        WIDTH,
+    },
+    polygons::{
+        click_position,
+        select_polygon,
+        Polygon,
+    },
+    shared_vertices::VertexPool,
+    state::{
+        Function,
+        State,
+    },
 };
 
+pub use cg_scanline::raster::Vertex;
+
 pub struct TrianglesPlugin;
 
 impl Plugin for TrianglesPlugin {
@@ -22,22 +57,21 @@ impl Plugin for TrianglesPlugin {
     }
 }
 
-#[derive(Debug, Clone)]
+// --------------------
+// vértice de um triângulo, ou o índice de um vértice de um polígono
+// --------------------
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum VertexOrder {
     First,
     Middle,
     Last,
+    Index(usize),
 }
 
 #[derive(Component)]
 pub struct VertexSelector(pub VertexOrder);
 
-#[derive(Debug, Clone)]
-pub struct Vertex {
-    pub color: [u8; 3],
-    pub position: [f32; 2],
-}
-
 #[derive(Debug, Component)]
 pub struct Triangle {
     pub first: Vertex,
@@ -46,10 +80,160 @@ pub struct Triangle {
     pub redraw: bool,
     pub index: usize,
     pub edges_color: Option<[u8; 3]>,
+    pub blend_mode: BlendMode,
+    pub color_space: ColorSpace,
+    pub texture: Option<TextureMapping>,
+    pub perspective_correct: bool,
+    pub shading: ShadingModel,
+    pub shader: Arc<dyn FragmentShader>,
+    // índices no VertexPool dos vértices soldados a outros triângulos
+    pub shared: [Option<usize>; 3],
 }
 
-#[derive(Component)]
-pub struct TriangleSprite(pub Option<Entity>); // entity do SpriteBundle
+impl Triangle {
+    pub fn corners(&self) -> [&Vertex; 3] {
+        [&self.first, &self.middle, &self.last]
+    }
+
+    pub fn corners_mut(&mut self) -> [&mut Vertex; 3] {
+        [&mut self.first, &mut self.middle, &mut self.last]
+    }
+
+    pub fn to_raster(&self) -> raster::Triangle {
+        raster::Triangle {
+            vertices: [
+                self.first.clone(),
+                self.middle.clone(),
+                self.last.clone(),
+            ],
+            edges_color: self.edges_color,
+            blend_mode: self.blend_mode,
+            color_space: self.color_space,
+            texture: self.texture.clone(),
+            perspective_correct: self.perspective_correct,
+            shading: self.shading,
+            shader: self.shader.clone(),
+        }
+    }
+}
+
+// --------------------
+// triângulo com os mesmos valores de um recém-criado pela interface,
+// usado nos testes
+// --------------------
+
+#[cfg(test)]
+impl Triangle {
+    pub fn test(first: Vertex, middle: Vertex, last: Vertex) -> Self {
+        Self {
+            first,
+            middle,
+            last,
+            redraw: true,
+            index: 1,
+            edges_color: None,
+            blend_mode: BlendMode::Normal,
+            color_space: ColorSpace::Srgb,
+            texture: None,
+            perspective_correct: true,
+            shading: ShadingModel::Gouraud,
+            shader: Arc::new(Gouraud),
+            shared: [None; 3],
+        }
+    }
+}
+
+
+// --------------------
+// demonstração da correção de perspectiva: o mesmo quadrado inclinado
+// (um "chão" que se afasta do observador), com uma textura xadrez,
+// projetado duas vezes lado a lado. o da esquerda usa interpolação
+// afim e o da direita, correção de perspectiva.
+// --------------------
+
+pub fn tilted_quads(first_index: usize) -> Vec<Triangle> {
+    let texture = Arc::new(checker_texture(256, 32));
+    let focal_length = 300.0;
+
+    // ponto (s, t) do quadrado, com t crescendo para o fundo
+    let corner = |center_x: f32, s: f32, t: f32| {
+        let x = 2.0 * s - 1.0;
+        let y = 2.0 * t - 1.0;
+        let z = 2.0 + 4.0 * t;
+
+        Vertex {
+            uv: [s, t],
+            w: z,
+            ..Vertex::new([center_x + focal_length * x / z, HEIGHT / 2.0 + focal_length * y / z], [255, 255, 255, 255])
+        }
+    };
+
+    let mut triangles = Vec::new();
+
+    for (center_x, perspective_correct) in [(WIDTH / 4.0, false), (WIDTH * 3.0 / 4.0, true)] {
+        for [first, middle, last] in [[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)], [(0.0, 0.0), (1.0, 1.0), (0.0, 1.0)]] {
+            triangles.push(Triangle {
+                first: corner(center_x, first.0, first.1),
+                middle: corner(center_x, middle.0, middle.1),
+                last: corner(center_x, last.0, last.1),
+                redraw: true,
+                index: first_index + triangles.len(),
+                edges_color: None,
+                blend_mode: BlendMode::Normal,
+                color_space: ColorSpace::Srgb,
+                texture: Some(TextureMapping::new(texture.clone())),
+                perspective_correct,
+                shading: ShadingModel::Gouraud,
+                shader: Arc::new(Gouraud),
+                shared: [None; 3],
+            });
+        }
+    }
+
+    triangles
+}
+
+
+// --------------------
+// textura xadrez gerada, sem arquivo. o nome guarda os parâmetros, para
+// que a textura possa ser gerada de novo ao abrir uma cena salva.
+// --------------------
+
+fn checker_texture(size: usize, cell: usize) -> Texture {
+    let mut pixels = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let value = if (x / cell) % 2 == (y / cell) % 2 { 240 } else { 40 };
+            pixels.extend([value, value, value, 255]);
+        }
+    }
+
+    Texture {
+        name: format!("{}{}x{}", CHECKER_PREFIX, size, cell),
+        width: size,
+        height: size,
+        pixels,
+    }
+}
+
+const CHECKER_PREFIX: &str = "xadrez:";
+
+fn checker_parameters(name: &str) -> Option<(usize, usize)> {
+    let (size, cell) = name.strip_prefix(CHECKER_PREFIX)?.split_once('x')?;
+    let (size, cell): (usize, usize) = (size.parse().ok()?, cell.parse().ok()?);
+
+    (size > 0 && cell > 0 && size <= 4096).then_some((size, cell))
+}
+
+// a textura foi gerada por checker_texture, e não carregada de um arquivo
+pub fn is_generated(texture: &Texture) -> bool {
+    checker_parameters(&texture.name).is_some()
+}
+
+// gera de novo a textura gerada com esse nome
+pub fn generated_texture(name: &str) -> Option<Texture> {
+    checker_parameters(name).map(|(size, cell)| checker_texture(size, cell))
+}
 
 
 fn creating(
@@ -57,6 +241,7 @@ fn creating(
     mut egui_contexts: EguiContexts,
     input: Res<Input<MouseButton>>,
     mut state: ResMut<State>,
+    mut vertex_pool: ResMut<VertexPool>,
     window_query: Query<&Window, With<PrimaryWindow>>,
 ) {
     if let Function::Create = state.function {
@@ -64,23 +249,32 @@ fn creating(
         let ctx = egui_contexts.ctx_mut();
 
         // --------------------
-        // se nem todos os vértices tiverem sido definidos,
-        // adiciona os pontos de clique no vetor new_triangle
+        // se nem todos os vértices tiverem sido definidos (ou se um
+        // polígono estiver sendo desenhado), adiciona os pontos de clique
+        // no vetor new_triangle. no polígono, um clique sobre o primeiro
+        // ponto o fecha.
         // --------------------
 
-        if state.new_triangle.len() < 3 {
+        if state.new_triangle.len() < 3 || state.draw_polygon {
             if input.just_pressed(MouseButton::Left) && !(ctx.is_using_pointer() || ctx.is_pointer_over_area()) {
                 if let Some(cursor_position) = window.cursor_position() {
                     let position: [f32; 2] = [
                         cursor_position.x,
                         window.height() - cursor_position.y,
                     ];
-                    let color: [u8; 3] = state.vertex_color_picker.clone();
 
-                    state.new_triangle.push(Vertex {
-                        position,
-                        color,
-                    });
+                    if state.draw_polygon && state.new_triangle.len() >= 3 {
+                        let first = state.new_triangle[0].position;
+                        if (position[0] - first[0]).abs() < 8.0 && (position[1] - first[1]).abs() < 8.0 {
+                            close_drawn_polygon(&mut commands, &mut state, &mut vertex_pool);
+                            return;
+                        }
+                    }
+
+                    let color: [u8; 4] = state.vertex_color_picker;
+                    let uv = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]].get(state.new_triangle.len()).copied().unwrap_or_default();
+
+                    state.new_triangle.push(Vertex { uv, ..Vertex::new(position, color) });
 
                     state.spawn_vertex_selectors = true;
                 }
@@ -104,28 +298,59 @@ fn creating(
                 } else {
                     None
                 },
+                blend_mode: BlendMode::Normal,
+                color_space: ColorSpace::Srgb,
+                texture: None,
+                perspective_correct: true,
+                shading: ShadingModel::Gouraud,
+                shader: Arc::new(Gouraud),
+                shared: [None; 3],
             };
 
             state.first_position_x_string = triangle.first.position[0].to_string();
             state.first_position_y_string = triangle.first.position[1].to_string();
+            state.first_depth_string = triangle.first.depth.to_string();
+            state.first_uv_u_string = triangle.first.uv[0].to_string();
+            state.first_uv_v_string = triangle.first.uv[1].to_string();
+            state.first_w_string = triangle.first.w.to_string();
+            state.first_normal_x_string = triangle.first.normal[0].to_string();
+            state.first_normal_y_string = triangle.first.normal[1].to_string();
+            state.first_normal_z_string = triangle.first.normal[2].to_string();
 
             state.middle_position_x_string = triangle.middle.position[0].to_string();
             state.middle_position_y_string = triangle.middle.position[1].to_string();
+            state.middle_depth_string = triangle.middle.depth.to_string();
+            state.middle_uv_u_string = triangle.middle.uv[0].to_string();
+            state.middle_uv_v_string = triangle.middle.uv[1].to_string();
+            state.middle_w_string = triangle.middle.w.to_string();
+            state.middle_normal_x_string = triangle.middle.normal[0].to_string();
+            state.middle_normal_y_string = triangle.middle.normal[1].to_string();
+            state.middle_normal_z_string = triangle.middle.normal[2].to_string();
 
             state.last_position_x_string = triangle.last.position[0].to_string();
             state.last_position_y_string = triangle.last.position[1].to_string();
+            state.last_depth_string = triangle.last.depth.to_string();
+            state.last_uv_u_string = triangle.last.uv[0].to_string();
+            state.last_uv_v_string = triangle.last.uv[1].to_string();
+            state.last_w_string = triangle.last.w.to_string();
+            state.last_normal_x_string = triangle.last.normal[0].to_string();
+            state.last_normal_y_string = triangle.last.normal[1].to_string();
+            state.last_normal_z_string = triangle.last.normal[2].to_string();
 
             state.first_color_r_string = triangle.first.color[0].to_string();
             state.first_color_g_string = triangle.first.color[1].to_string();
             state.first_color_b_string = triangle.first.color[2].to_string();
+            state.first_color_a_string = triangle.first.color[3].to_string();
 
             state.middle_color_r_string = triangle.middle.color[0].to_string();
             state.middle_color_g_string = triangle.middle.color[1].to_string();
             state.middle_color_b_string = triangle.middle.color[2].to_string();
+            state.middle_color_a_string = triangle.middle.color[3].to_string();
 
             state.last_color_r_string = triangle.last.color[0].to_string();
             state.last_color_g_string = triangle.last.color[1].to_string();
             state.last_color_b_string = triangle.last.color[2].to_string();
+            state.last_color_a_string = triangle.last.color[3].to_string();
 
             if let Some(edges_color) = triangle.edges_color {
                 state.edges_color_r_string = edges_color[0].to_string();
@@ -133,10 +358,7 @@ fn creating(
                 state.edges_color_b_string = edges_color[2].to_string();     
             }
 
-            let entity = commands.spawn((
-                triangle,
-                TriangleSprite(None),
-            )).id();
+            let entity = commands.spawn(triangle).id();
 
             state.function = Function::Modify(entity);
             state.new_triangle.clear();
@@ -146,7 +368,91 @@ fn creating(
 }
 
 
-fn modifying(
+// --------------------
+// fecha o polígono desenhado em new_triangle e o corta em triângulos
+// comuns, com as cores dos pontos clicados
+// --------------------
+
+pub fn close_drawn_polygon(commands: &mut Commands, state: &mut State, vertex_pool: &mut VertexPool) {
+    let points: Vec<[f32; 2]> = state.new_triangle.iter().map(|vertex| vertex.position).collect();
+
+    let Some(faces) = raster::ear_clipping(&points) else {
+        state.triangulation_message = Some(String::from("O polígono se cruza e não pode ser triangulado."));
+        return;
+    };
+
+    let vertices = std::mem::take(&mut state.new_triangle);
+    spawn_faces(commands, state, vertex_pool, &vertices, &faces);
+
+    state.function = Function::None;
+    state.draw_polygon = false;
+    state.triangulation_message = None;
+    state.spawn_vertex_selectors = true;
+}
+
+
+// --------------------
+// spawna um triângulo para cada trinca de índices de vertices. os
+// triângulos já nascem soldados nos vértices em comum, e as coordenadas
+// de textura vêm da posição de cada vértice no retângulo que envolve
+// todos eles.
+// --------------------
+
+pub fn spawn_faces(
+    commands: &mut Commands,
+    state: &mut State,
+    vertex_pool: &mut VertexPool,
+    vertices: &[Vertex],
+    faces: &[[usize; 3]],
+) {
+    let mut min = [f32::INFINITY; 2];
+    let mut max = [f32::NEG_INFINITY; 2];
+    for vertex in vertices {
+        for axis in 0..2 {
+            min[axis] = min[axis].min(vertex.position[axis]);
+            max[axis] = max[axis].max(vertex.position[axis]);
+        }
+    }
+
+    let shared: Vec<usize> = vertices
+        .iter()
+        .map(|vertex| {
+            vertex_pool.add(Vertex {
+                uv: [0, 1].map(|axis| (vertex.position[axis] - min[axis]) / (max[axis] - min[axis]).max(1.0)),
+                ..vertex.clone()
+            })
+        })
+        .collect();
+
+    for face in faces {
+        let [first, middle, last] = face.map(|index| vertex_pool.vertices[shared[index]].clone());
+
+        commands.spawn(Triangle {
+            first,
+            middle,
+            last,
+            redraw: true,
+            index: state.triangles_count,
+            edges_color: if state.constant_edges {
+                Some(state.edges_color_picker)
+            } else {
+                None
+            },
+            blend_mode: BlendMode::Normal,
+            color_space: ColorSpace::Srgb,
+            texture: None,
+            perspective_correct: true,
+            shading: ShadingModel::Gouraud,
+            shader: Arc::new(Gouraud),
+            shared: face.map(|index| Some(shared[index])),
+        });
+
+        state.triangles_count += 1;
+    }
+}
+
+
+pub fn modifying(
     mut egui_contexts: EguiContexts,
     input: Res<Input<MouseButton>>,
     mut state: ResMut<State>,
@@ -180,6 +486,7 @@ fn modifying(
                                 state.first_color_r_string = triangle.first.color[0].to_string();
                                 state.first_color_g_string = triangle.first.color[1].to_string();
                                 state.first_color_b_string = triangle.first.color[2].to_string();
+                                state.first_color_a_string = triangle.first.color[3].to_string();
 
                                 triangle.redraw = true;
                             }
@@ -189,6 +496,7 @@ fn modifying(
                                 state.middle_color_r_string = triangle.middle.color[0].to_string();
                                 state.middle_color_g_string = triangle.middle.color[1].to_string();
                                 state.middle_color_b_string = triangle.middle.color[2].to_string();
+                                state.middle_color_a_string = triangle.middle.color[3].to_string();
 
                                 triangle.redraw = true;
                             }
@@ -198,9 +506,11 @@ fn modifying(
                                 state.last_color_r_string = triangle.last.color[0].to_string();
                                 state.last_color_g_string = triangle.last.color[1].to_string();
                                 state.last_color_b_string = triangle.last.color[2].to_string();
+                                state.last_color_a_string = triangle.last.color[3].to_string();
 
                                 triangle.redraw = true;
                             }
+                            VertexOrder::Index(_) => {}
                         }
 
                         state.spawn_vertex_selectors = true;
@@ -245,6 +555,7 @@ fn modifying(
                             state.last_position_x_string = triangle.last.position[0].to_string();
                             state.last_position_y_string = triangle.last.position[1].to_string();
                         }
+                        VertexOrder::Index(_) => {}
                     };
 
                     triangle.redraw = true;
@@ -285,364 +596,446 @@ fn modifying(
 }
 
 
-fn redrawing(
-    mut commands: Commands,
+pub fn redrawing(
+    mut canvas: ResMut<Canvas>,
     mut images: ResMut<Assets<Image>>,
-    mut triangles_query: Query<(&mut Triangle, &mut TriangleSprite)>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut triangles_query: Query<(Entity, &mut Triangle)>,
+    mut polygons_query: Query<(Entity, &mut Polygon)>,
+    mut removed_triangles: RemovedComponents<Triangle>,
+    mut removed_polygons: RemovedComponents<Polygon>,
 ) {
-    for (mut triangle, mut sprite) in triangles_query.iter_mut() {
-        if triangle.redraw {
-            let window = window_query.single();
+    let canvas = canvas.as_mut();
+    let (width, height) = (canvas.framebuffer.width, canvas.framebuffer.height);
 
-            if let Some(entity) = sprite.0 {
-                commands.entity(entity).despawn();
-            }
+    // --------------------
+    // a região antiga e a nova de cada triângulo ou polígono alterado
+    // ou removido precisam ser repintadas
+    // --------------------
 
-            let mut image = Image::new_fill(
-                Extent3d {
-                    width: window.width() as u32,
-                    height: window.height() as u32,
-                    depth_or_array_layers: 1,
-                },
-                TextureDimension::D2,
-                &[0, 0, 0, 0],
-                TextureFormat::Rgba8Unorm,
-            );
-            image.texture_descriptor.usage =
-                TextureUsages::COPY_DST | 
-                TextureUsages::STORAGE_BINDING | 
-                TextureUsages::TEXTURE_BINDING;
-            
-            render(&triangle, &mut image.data, window.width() as usize, window.height() as usize);
-
-            let image = images.add(image);
-
-            let entity = commands.spawn(SpriteBundle {
-                sprite: Sprite {
-                    custom_size: Some(Vec2::new(window.width(), window.height())),
-                    ..default()
-                },
-                texture: image.clone(),
-                transform: Transform::from_translation(Vec3::new(
-                    window.width() / 2.0,
-                    window.height() / 2.0,
-                    triangle.index as f32,
-                )),
-                ..default()
-            }).id();
-
-            sprite.0 = Some(entity);
+    let mut dirty: Vec<PixelRect> = Vec::new();
+
+    for entity in removed_triangles.read().chain(removed_polygons.read()) {
+        if let Some(old) = canvas.painted.remove(&entity) {
+            dirty.push(old);
+        }
+    }
+
+    let mut update = |entity: Entity, bounds: Option<PixelRect>| {
+        if let Some(old) = canvas.painted.remove(&entity) {
+            dirty.push(old);
+        }
+        if let Some(new) = bounds {
+            canvas.painted.insert(entity, new);
+            dirty.push(new);
+        }
+    };
+
+    for (entity, mut triangle) in triangles_query.iter_mut() {
+        if triangle.redraw {
+            update(entity, triangle.to_raster().bounds(width, height));
             triangle.redraw = false;
         }
     }
+
+    for (entity, mut polygon) in polygons_query.iter_mut() {
+        if polygon.redraw {
+            update(entity, polygon.to_raster().bounds(width, height));
+            polygon.redraw = false;
+        }
+    }
+
+    if dirty.is_empty() {
+        return;
+    }
+
+    let start = Instant::now();
+
+    // --------------------
+    // repinta, dentro de cada região suja, os triângulos e polígonos
+    // que a cruzam, do menor para o maior índice
+    // --------------------
+
+    let mut shapes: Vec<(usize, raster::Shape, PixelRect)> = triangles_query
+        .iter()
+        .filter_map(|(entity, triangle)| {
+            canvas.painted.get(&entity).map(|bounds| (triangle.index, raster::Shape::Triangle(triangle.to_raster()), *bounds))
+        })
+        .chain(polygons_query.iter().filter_map(|(entity, polygon)| {
+            canvas.painted.get(&entity).map(|bounds| (polygon.index, raster::Shape::Polygon(polygon.to_raster()), *bounds))
+        }))
+        .collect();
+    shapes.sort_by_key(|(index, _, _)| *index);
+
+    for region in merge_rects(dirty) {
+        let intersecting: Vec<raster::Shape> = shapes
+            .iter()
+            .filter(|(_, _, bounds)| bounds.intersects(&region))
+            .map(|(_, shape, _)| shape.clone())
+            .collect();
+
+        render_parallel(canvas.rasterizer, &canvas.settings, &intersecting, &mut canvas.framebuffer, &region);
+        canvas.upload(&region, &mut images);
+    }
+
+    canvas.render_time = start.elapsed();
 }
 
 
+// --------------------
+// seleciona o triângulo ou polígono clicado. se o clique cair sobre mais
+// de uma forma, fica com a pintada por último (a de maior índice), que é
+// a que aparece na tela.
+// --------------------
+
 fn selecting(
     mut egui_contexts: EguiContexts,
     input: Res<Input<MouseButton>>,
     mut state: ResMut<State>,
     triangles_query: Query<(Entity, &Triangle)>,
+    polygons_query: Query<(Entity, &Polygon)>,
     window_query: Query<&Window, With<PrimaryWindow>>,
 ) {
     if let Function::Select = state.function {
         let window = window_query.single();
-        let ctx = egui_contexts.ctx_mut();
-
-        if input.just_pressed(MouseButton::Left) && !(ctx.is_using_pointer() || ctx.is_pointer_over_area()) {
-            if let Some(cursor_position) = window.cursor_position() {
-                let click = (cursor_position.x, window.height() - cursor_position.y);
-                for (entity, triangle) in triangles_query.iter() {
-                    if is_inside(click, triangle) {
-                        state.function = Function::Modify(entity);
-                        state.spawn_vertex_selectors = true;
-                        
-                        state.first_position_x_string = triangle.first.position[0].to_string();
-                        state.first_position_y_string = triangle.first.position[1].to_string();
 
-                        state.middle_position_x_string = triangle.middle.position[0].to_string();
-                        state.middle_position_y_string = triangle.middle.position[1].to_string();
-
-                        state.last_position_x_string = triangle.last.position[0].to_string();
-                        state.last_position_y_string = triangle.last.position[1].to_string();
-
-                        state.first_color_r_string = triangle.first.color[0].to_string();
-                        state.first_color_g_string = triangle.first.color[1].to_string();
-                        state.first_color_b_string = triangle.first.color[2].to_string();
-
-                        state.middle_color_r_string = triangle.middle.color[0].to_string();
-                        state.middle_color_g_string = triangle.middle.color[1].to_string();
-                        state.middle_color_b_string = triangle.middle.color[2].to_string();
-
-                        state.last_color_r_string = triangle.last.color[0].to_string();
-                        state.last_color_g_string = triangle.last.color[1].to_string();
-                        state.last_color_b_string = triangle.last.color[2].to_string();
-
-                        if let Some(edges_color) = triangle.edges_color {
-                            state.edges_color_r_string = edges_color[0].to_string();
-                            state.edges_color_g_string = edges_color[1].to_string();
-                            state.edges_color_b_string = edges_color[2].to_string();
-
-                            state.constant_edges = true;
-                        }
-                    }
+        if let Some(position) = click_position(&mut egui_contexts, &input, MouseButton::Left, window) {
+            let click = (position[0], position[1]);
+
+            let triangle = triangles_query
+                .iter()
+                .filter(|(_, triangle)| is_inside(click, triangle))
+                .max_by_key(|(_, triangle)| triangle.index);
+            let polygon = polygons_query
+                .iter()
+                .filter(|(_, polygon)| polygon.to_raster().contains(click))
+                .max_by_key(|(_, polygon)| polygon.index);
+
+            match (triangle, polygon) {
+                (Some((_, triangle)), Some((entity, polygon))) if polygon.index > triangle.index => {
+                    select_polygon(&mut state, entity, polygon);
                 }
+                (Some((entity, triangle)), _) => select_triangle(&mut state, entity, triangle),
+                (None, Some((entity, polygon))) => select_polygon(&mut state, entity, polygon),
+                (None, None) => {}
             }
         }
     }
 }
 
 
-fn render(
-    triangle: &Triangle,
-    image: &mut Vec<u8>,
-    width: usize,
-    height: usize,
-) {
-    let mut edges: Vec<Vec<(f32, f32)>> = Vec::new();
+fn select_triangle(state: &mut State, entity: Entity, triangle: &Triangle) {
+    state.function = Function::Modify(entity);
+    state.spawn_vertex_selectors = true;
+
+    state.first_position_x_string = triangle.first.position[0].to_string();
+    state.first_position_y_string = triangle.first.position[1].to_string();
+    state.first_depth_string = triangle.first.depth.to_string();
+    state.first_uv_u_string = triangle.first.uv[0].to_string();
+    state.first_uv_v_string = triangle.first.uv[1].to_string();
+    state.first_w_string = triangle.first.w.to_string();
+    state.first_normal_x_string = triangle.first.normal[0].to_string();
+    state.first_normal_y_string = triangle.first.normal[1].to_string();
+    state.first_normal_z_string = triangle.first.normal[2].to_string();
+
+    state.middle_position_x_string = triangle.middle.position[0].to_string();
+    state.middle_position_y_string = triangle.middle.position[1].to_string();
+    state.middle_depth_string = triangle.middle.depth.to_string();
+    state.middle_uv_u_string = triangle.middle.uv[0].to_string();
+    state.middle_uv_v_string = triangle.middle.uv[1].to_string();
+    state.middle_w_string = triangle.middle.w.to_string();
+    state.middle_normal_x_string = triangle.middle.normal[0].to_string();
+    state.middle_normal_y_string = triangle.middle.normal[1].to_string();
+    state.middle_normal_z_string = triangle.middle.normal[2].to_string();
+
+    state.last_position_x_string = triangle.last.position[0].to_string();
+    state.last_position_y_string = triangle.last.position[1].to_string();
+    state.last_depth_string = triangle.last.depth.to_string();
+    state.last_uv_u_string = triangle.last.uv[0].to_string();
+    state.last_uv_v_string = triangle.last.uv[1].to_string();
+    state.last_w_string = triangle.last.w.to_string();
+    state.last_normal_x_string = triangle.last.normal[0].to_string();
+    state.last_normal_y_string = triangle.last.normal[1].to_string();
+    state.last_normal_z_string = triangle.last.normal[2].to_string();
+
+    state.first_color_r_string = triangle.first.color[0].to_string();
+    state.first_color_g_string = triangle.first.color[1].to_string();
+    state.first_color_b_string = triangle.first.color[2].to_string();
+    state.first_color_a_string = triangle.first.color[3].to_string();
+
+    state.middle_color_r_string = triangle.middle.color[0].to_string();
+    state.middle_color_g_string = triangle.middle.color[1].to_string();
+    state.middle_color_b_string = triangle.middle.color[2].to_string();
+    state.middle_color_a_string = triangle.middle.color[3].to_string();
+
+    state.last_color_r_string = triangle.last.color[0].to_string();
+    state.last_color_g_string = triangle.last.color[1].to_string();
+    state.last_color_b_string = triangle.last.color[2].to_string();
+    state.last_color_a_string = triangle.last.color[3].to_string();
 
-    // --------------------
-    // pinta arestas
-    // --------------------
-
-    for i in 0..3 {
-        let (
-            v0,
-            v1,
-        ) = if i == 0 {
-            (
-                triangle.first.clone(),
-                triangle.middle.clone(),
-            )
-        } else if i == 1 {
-            (
-                triangle.middle.clone(),
-                triangle.last.clone(),
-            )
-        } else {
-            (
-                triangle.last.clone(),
-                triangle.first.clone(),
-            )
-        };
+    if let Some(edges_color) = triangle.edges_color {
+        state.edges_color_r_string = edges_color[0].to_string();
+        state.edges_color_g_string = edges_color[1].to_string();
+        state.edges_color_b_string = edges_color[2].to_string();
 
-        let x0 = v0.position[0];
-        let y0 = v0.position[1];
-
-        let x1 = v1.position[0];
-        let y1 = v1.position[1];
-
-        let r0 = v0.color[0] as f32;
-        let g0 = v0.color[1] as f32;
-        let b0 = v0.color[2] as f32;
-
-        let r1 = v1.color[0] as f32;
-        let g1 = v1.color[1] as f32;
-        let b1 = v1.color[2] as f32;
-
-        let points = bresenham(x0, y0, x1, y1);
-        edges.push(points.clone());
-        let points_len = points.len() as f32;
-
-        let tr = (r1 - r0) / points_len;
-        let tg = (g1 - g0) / points_len;
-        let tb = (b1 - b0) / points_len;
-
-        let mut r = r0;
-        let mut g = g0;
-        let mut b = b0;
-
-        for (x, y) in points {
-            r += tr;
-            g += tg;
-            b += tb;
-    
-            let i = height - (y.round() as usize) - 1;
-            let j = x.round() as usize;
-            let index = (i * width + j) * 4;
-
-            image[index] = r.round() as u8;
-            image[index + 1] = g.round() as u8;
-            image[index + 2] = b.round() as u8;
-            image[index + 3] = 255;
-        }
+        state.constant_edges = true;
     }
+}
 
-    // --------------------
-    // pinta interior do triângulo
-    // --------------------
 
-    let x_min = triangle.last.position[0].min(triangle.middle.position[0].min(triangle.first.position[0])).round() as usize;
-    let x_max = triangle.last.position[0].max(triangle.middle.position[0].max(triangle.first.position[0])).round() as usize;
+fn is_inside(click: (f32, f32), triangle: &Triangle) -> bool {
+    triangle.to_raster().contains(click)
+}
 
-    let y_min = triangle.last.position[1].min(triangle.middle.position[1].min(triangle.first.position[1])).round() as usize;
-    let y_max = triangle.last.position[1].max(triangle.middle.position[1].max(triangle.first.position[1])).round() as usize;
 
-    for i in y_min..=y_max {
-        let i = height - i - 1;
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        asset::AssetPlugin,
+        ecs::system::RunSystemOnce,
+    };
 
-        // --------------------
-        // acha cor inicial e cor final
-        // --------------------
+    use cg_scanline::raster::FillRule;
 
-        let mut first_color: Option<[f32; 3]> = None;
-        let mut first_color_j: usize = 0;
+    use super::*;
+    use crate::{
+        canvas::CanvasPlugin,
+        shared_vertices::SharedVerticesPlugin,
+        state::StatePlugin,
+    };
 
-        let mut last_color: Option<[f32; 3]> = None;
-        let mut last_color_j: usize = 0;
-        
-        let mut after_first_line = false;
-
-        for j in x_min..=x_max {
-            let index = (i * width + j) * 4;
-            
-            if image[index + 3] > 0 && after_first_line == false {
-                first_color = Some(
-                    [
-                        image[index] as f32,
-                        image[index + 1] as f32,
-                        image[index + 2] as f32,
-                    ]
-                );
-                first_color_j = j;
-            } else if image[index + 3] == 0 && after_first_line == false && first_color.is_some() {
-                after_first_line = true;
-            } else if image[index + 3] > 0 && after_first_line == true && first_color.is_some() {
-                last_color = Some(
-                    [
-                        image[index] as f32,
-                        image[index + 1] as f32,
-                        image[index + 2] as f32,
-                    ]
-                );
-                last_color_j = j;
-                break;
-            }
-        }
+    fn app() -> App {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugins(AssetPlugin::default())
+            .init_asset::<Image>()
+            .add_plugins(CanvasPlugin)
+            .add_systems(Update, redrawing);
+        app
+    }
 
-        // --------------------
-        // realiza algoritmo da scanline com aritmética incremental
-        // --------------------
+    fn vertex(x: f32, y: f32, color: [u8; 4]) -> Vertex {
+        Vertex::new([x, y], color)
+    }
 
-        if let (Some(first_color), Some(last_color)) = (first_color, last_color) {
-            let points_len = (last_color_j - first_color_j) as f32;
-            let mut j = first_color_j + 1;
+    #[test]
+    fn repeated_edits_reuse_the_canvas_image() {
+        let mut app = app();
 
-            let tr = (last_color[0] - first_color[0]) / points_len;
-            let tg = (last_color[1] - first_color[1]) / points_len;
-            let tb = (last_color[2] - first_color[2]) / points_len;
+        let entity = app.world.spawn(Triangle::test(
+            vertex(10.0, 10.0, [255, 0, 0, 255]),
+            vertex(200.0, 40.0, [0, 255, 0, 255]),
+            vertex(90.0, 300.0, [0, 0, 255, 255]),
+        )).id();
 
-            let mut r = first_color[0];
-            let mut g = first_color[1];
-            let mut b = first_color[2];
+        app.update();
 
-            while j < last_color_j {
-                r += tr;
-                g += tg;
-                b += tb;
+        let images = app.world.resource::<Assets<Image>>().len();
+        let sprites = app.world.query::<&Sprite>().iter(&app.world).count();
+        assert_eq!(images, 1);
+        assert_eq!(sprites, 1);
 
-                let index = (i * width + j) * 4;
-                image[index] = r.round() as u8;
-                image[index + 1] = g.round() as u8;
-                image[index + 2] = b.round() as u8;
-                image[index + 3] = 255;
+        for i in 0..20 {
+            let mut triangle = app.world.get_mut::<Triangle>(entity).unwrap();
+            triangle.middle.position = [200.0 + i as f32 * 7.0, 40.0 + i as f32 * 3.0];
+            triangle.middle.color = [i * 10, 255, 0, 255];
+            triangle.redraw = true;
 
-                j += 1;
-            }
+            app.update();
+
+            assert_eq!(app.world.resource::<Assets<Image>>().len(), images);
+            assert_eq!(app.world.query::<&Sprite>().iter(&app.world).count(), sprites);
         }
+
+        // a imagem exibida deve refletir o framebuffer após a última edição
+        let canvas = app.world.resource::<Canvas>();
+        let image = app.world.resource::<Assets<Image>>().get(&canvas.image).unwrap();
+        assert_eq!(image.data, canvas.framebuffer.pixels);
     }
 
-    // --------------------
-    // pinta as arestas com uma cor constante caso possua
-    // --------------------
+    #[test]
+    fn incremental_redraw_matches_full_redraw() {
+        let mut app = app();
+
+        let mut entities = Vec::new();
+        for i in 0..12 {
+            let offset = i as f32 * 37.0;
+            entities.push(app.world.spawn(Triangle {
+                index: i + 1,
+                edges_color: if i % 3 == 0 { Some([10, 20, 30]) } else { None },
+                blend_mode: BlendMode::ALL[i % BlendMode::ALL.len()],
+                color_space: ColorSpace::ALL[i % ColorSpace::ALL.len()],
+                perspective_correct: i % 2 == 0,
+                shading: ShadingModel::ALL[i % ShadingModel::ALL.len()],
+                ..Triangle::test(
+                    vertex(20.0 + offset, 15.0 + offset * 0.5, [255, 0, 0, 255]),
+                    vertex(180.0 + offset, 60.0, [0, 255, 0, 160]),
+                    vertex(70.0 + offset * 0.8, 240.0 + offset * 0.3, [0, 0, 255, 255]),
+                )
+            }).id());
+        }
 
-    if let Some(edges_color) = triangle.edges_color {
-        for points in edges {
-            for (x, y) in points {        
-                let r = edges_color[0];
-                let g = edges_color[1];
-                let b = edges_color[2];
-        
-                let i = height - (y.round() as usize) - 1;
-                let j = x.round() as usize;
-                let index = (i * width + j) * 4;
-    
-                image[index] = r;
-                image[index + 1] = g;
-                image[index + 2] = b;
-                image[index + 3] = 255;
-            }
+        app.update();
+
+        for (i, &entity) in entities.iter().enumerate().step_by(4) {
+            let mut triangle = app.world.get_mut::<Triangle>(entity).unwrap();
+            triangle.last.position = [300.0 + i as f32 * 11.0, 120.0];
+            triangle.redraw = true;
         }
-    }
-}
+        app.world.despawn(entities[5]);
+
+        app.update();
 
+        // repinta tudo do zero e compara com o resultado incremental
+        let mut triangles: Vec<&Triangle> = app.world.query::<&Triangle>().iter(&app.world).collect();
+        triangles.sort_by_key(|triangle| triangle.index);
 
-fn bresenham(x0: f32, y0: f32, x1: f32, y1: f32) -> Vec<(f32, f32)> {
-    let x0 = x0.round();
-    let y0 = y0.round();
-    let x1 = x1.round();
-    let y1 = y1.round();
+        let canvas = app.world.resource::<Canvas>();
+        let mut expected = raster::Framebuffer::new(canvas.framebuffer.width, canvas.framebuffer.height);
+        for triangle in triangles {
+            raster::render(&triangle.to_raster(), &mut expected, &canvas.settings);
+        }
 
-    let mut result = Vec::new();
-    
-    let dx = (x1 - x0).abs();
-    let dy = (y1 - y0).abs();
+        assert!(canvas.framebuffer == expected);
 
-    let sx = if x0 < x1 { 1.0 } else { -1.0 };
-    let sy = if y0 < y1 { 1.0 } else { -1.0 };
+        let image = app.world.resource::<Assets<Image>>().get(&canvas.image).unwrap();
+        assert!(image.data == expected.pixels);
+    }
 
-    let mut x = x0;
-    let mut y = y0;
+    #[test]
+    fn deleting_a_triangle_clears_its_pixels() {
+        let mut app = app();
 
-    let mut err = dx - dy;
+        let entity = app.world.spawn(Triangle::test(
+            vertex(10.0, 10.0, [255, 0, 0, 255]),
+            vertex(200.0, 40.0, [0, 255, 0, 255]),
+            vertex(90.0, 300.0, [0, 0, 255, 255]),
+        )).id();
 
-    while (x - x1).abs() > 0.1 || (y - y1).abs() > 0.1 {
-        result.push((x, y));
+        app.update();
+        app.world.despawn(entity);
+        app.update();
 
-        let e2 = 2.0 * err;
+        let canvas = app.world.resource::<Canvas>();
+        let image = app.world.resource::<Assets<Image>>().get(&canvas.image).unwrap();
+        assert!(image.data.iter().all(|&byte| byte == 0));
+        assert_eq!(app.world.resource::<Assets<Image>>().len(), 1);
+    }
 
-        if e2 > -dy {
-            err -= dy;
-            x += sx;
-        }
+    #[test]
+    fn polygons_are_painted_in_index_order_with_triangles() {
+        let mut app = app();
+
+        let triangle = app.world.spawn(Triangle {
+            index: 2,
+            ..Triangle::test(
+                vertex(10.0, 10.0, [255, 0, 0, 255]),
+                vertex(300.0, 40.0, [0, 255, 0, 255]),
+                vertex(90.0, 300.0, [0, 0, 255, 255]),
+            )
+        }).id();
+
+        // estrela que se cruza, pintada antes do triângulo
+        let polygon = app.world.spawn(Polygon {
+            vertices: vec![
+                vertex(100.0, 20.0, [255, 255, 0, 255]),
+                vertex(160.0, 200.0, [255, 255, 0, 255]),
+                vertex(10.0, 90.0, [0, 255, 255, 255]),
+                vertex(190.0, 90.0, [0, 255, 255, 255]),
+                vertex(40.0, 200.0, [255, 0, 255, 255]),
+            ],
+            holes: Vec::new(),
+            redraw: true,
+            index: 1,
+            edges_color: Some([0, 0, 0]),
+            blend_mode: BlendMode::Multiply,
+            fill_rule: FillRule::NonZero,
+        }).id();
+
+        let expected = |app: &mut App| {
+            let triangle = app.world.get::<Triangle>(triangle).unwrap().to_raster();
+            let polygon = app.world.get::<Polygon>(polygon).unwrap().to_raster();
+            let canvas = app.world.resource::<Canvas>();
+
+            let mut expected = raster::Framebuffer::new(canvas.framebuffer.width, canvas.framebuffer.height);
+            raster::render_polygon(&polygon, &mut expected, &canvas.settings);
+            raster::render(&triangle, &mut expected, &canvas.settings);
+            expected
+        };
 
-        if e2 < dx {
-            err += dx;
-            y += sy;
+        app.update();
+        let framebuffer = expected(&mut app);
+        assert!(app.world.resource::<Canvas>().framebuffer == framebuffer);
+
+        {
+            let mut polygon = app.world.get_mut::<Polygon>(polygon).unwrap();
+            polygon.vertices[0].position = [250.0, 10.0];
+            polygon.holes.push(vec![
+                vertex(80.0, 80.0, [0, 0, 255, 255]),
+                vertex(120.0, 80.0, [0, 0, 255, 255]),
+                vertex(100.0, 110.0, [0, 0, 255, 255]),
+            ]);
+            polygon.fill_rule = FillRule::EvenOdd;
+            polygon.redraw = true;
         }
+        app.update();
+        let framebuffer = expected(&mut app);
+        assert!(app.world.resource::<Canvas>().framebuffer == framebuffer);
+
+        app.world.despawn(polygon);
+        app.update();
+        let triangle = app.world.get::<Triangle>(triangle).unwrap().to_raster();
+        let canvas = app.world.resource::<Canvas>();
+        let mut expected = raster::Framebuffer::new(canvas.framebuffer.width, canvas.framebuffer.height);
+        raster::render(&triangle, &mut expected, &canvas.settings);
+        assert!(canvas.framebuffer == expected);
     }
 
-    result.push((x1, y1));
-    result
-}
+    #[test]
+    fn drawn_polygon_is_cut_into_triangles_with_the_clicked_colors() {
+        let mut app = app();
+        app.add_plugins(StatePlugin).add_plugins(SharedVerticesPlugin);
+        app.update();
+
+        // um "U": o entalhe do meio não pode ser coberto
+        let points = [[20.0, 20.0], [140.0, 20.0], [140.0, 140.0], [100.0, 140.0], [100.0, 60.0], [60.0, 60.0], [60.0, 140.0], [20.0, 140.0]];
+        let colors = [[255, 0, 0, 255], [0, 255, 0, 255]];
+        {
+            let mut state = app.world.resource_mut::<State>();
+            state.function = Function::Create;
+            state.draw_polygon = true;
+            state.new_triangle = points
+                .iter()
+                .enumerate()
+                .map(|(i, point)| vertex(point[0], point[1], colors[i % 2]))
+                .collect();
+        }
 
+        app.world.run_system_once(|mut commands: Commands, mut state: ResMut<State>, mut vertex_pool: ResMut<VertexPool>| {
+            close_drawn_polygon(&mut commands, &mut state, &mut vertex_pool);
+        });
+        app.update();
 
-fn barycentric_coordinates(p: (f32, f32), a: (f32, f32), b: (f32, f32), c: (f32, f32)) -> (f32, f32, f32) {
-    let v0 = (c.0 - a.0, c.1 - a.1);
-    let v1 = (b.0 - a.0, b.1 - a.1);
-    let v2 = (p.0 - a.0, p.1 - a.1);
+        let state = app.world.resource::<State>();
+        assert!(state.function == Function::None);
+        assert!(state.new_triangle.is_empty());
+        assert!(!state.draw_polygon);
 
-    let dot00 = v0.0 * v0.0 + v0.1 * v0.1;
-    let dot01 = v0.0 * v1.0 + v0.1 * v1.1;
-    let dot02 = v0.0 * v2.0 + v0.1 * v2.1;
-    let dot11 = v1.0 * v1.0 + v1.1 * v1.1;
-    let dot12 = v1.0 * v2.0 + v1.1 * v2.1;
+        let mut triangles: Vec<&Triangle> = app.world.query::<&Triangle>().iter(&app.world).collect();
+        triangles.sort_by_key(|triangle| triangle.index);
+        assert_eq!(triangles.len(), points.len() - 2);
 
-    let inv_denom = 1.0 / (dot00 * dot11 - dot01 * dot01);
-    let u = (dot11 * dot02 - dot01 * dot12) * inv_denom;
-    let v = (dot00 * dot12 - dot01 * dot02) * inv_denom;
+        for triangle in triangles {
+            for vertex in [&triangle.first, &triangle.middle, &triangle.last] {
+                let i = points.iter().position(|point| *point == vertex.position).unwrap();
+                assert_eq!(vertex.color, colors[i % 2]);
+            }
+        }
 
-    (u, v, 1.0 - u - v)
+        let framebuffer = &app.world.resource::<Canvas>().framebuffer;
+        assert!(framebuffer.pixel(40, 100)[3] > 0);
+        assert!(framebuffer.pixel(120, 100)[3] > 0);
+        assert!(framebuffer.pixel(80, 40)[3] > 0);
+        assert_eq!(framebuffer.pixel(80, 100)[3], 0);
+    }
 }
-
-
-fn is_inside(click: (f32, f32), triangle: &Triangle) -> bool {
-    let (u, v, _w) = barycentric_coordinates(
-        click, 
-        (triangle.first.position[0], triangle.first.position[1]), 
-        (triangle.middle.position[0], triangle.middle.position[1]), 
-        (triangle.last.position[0], triangle.last.position[1]));
-
-    u >= 0.0 && v >= 0.0 && u + v <= 1.0
-}
\ No newline at end of file
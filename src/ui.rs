@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use bevy::{
     prelude::*,
     sprite::MaterialMesh2dBundle,
@@ -7,18 +9,46 @@ use bevy_egui::{
     EguiContexts,
 };
 
+use cg_scanline::raster;
+
 use crate::{
+    canvas::{
+        compare_rasterizers,
+        export_png,
+        load_texture,
+        Canvas,
+    },
     constants::{
-        HEIGHT, 
+        HEIGHT,
         WIDTH,
-    }, 
+    },
+    delaunay::{
+        load_points,
+        triangulate_point_cloud,
+    },
+    scene::{
+        load_scene,
+        save_scene,
+    },
+    shared_vertices::{
+        unweld,
+        weld,
+        VertexPool,
+    },
     state::{
         Function,
         State,
     }, 
+    polygons::{
+        close_hole,
+        close_polygon,
+        Polygon,
+    },
     triangles::{
+        close_drawn_polygon,
+        tilted_quads,
         Triangle, 
-        TriangleSprite, 
+        Vertex,
         VertexOrder, 
         VertexSelector,
     }
@@ -34,13 +64,16 @@ impl Plugin for UIPlugin {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_ui(
     mut commands: Commands,
     mut contexts: EguiContexts,
+    mut canvas: ResMut<Canvas>,
     mut state: ResMut<State>,
     vertex_selector_query: Query<(Entity, &VertexSelector)>,
-    mut triangles_query: Query<&mut Triangle>,
-    triangle_sprites_query: Query<&TriangleSprite>,
+    mut triangles_query: Query<(Entity, &mut Triangle)>,
+    mut polygons_query: Query<&mut Polygon>,
+    mut vertex_pool: ResMut<VertexPool>,
 ) {
     egui::Window::new("Opções")
         .fixed_size([150.0, 200.0])
@@ -54,18 +87,190 @@ fn update_ui(
                             state.new_triangle.clear();
                             state.function = Function::Create;
                         }
+                        if ui.add(egui::Button::new("Adicionar polígono")).clicked() {
+                            state.new_polygon.clear();
+                            state.function = Function::CreatePolygon;
+                        }
+                        if ui.add(egui::Button::new("Delaunay")).clicked() {
+                            state.point_cloud.clear();
+                            state.points_message = None;
+                            state.function = Function::CreateDelaunay;
+                        }
                         if ui.add(egui::Button::new("Modificar")).clicked() {
                             state.function = Function::Select;
                         }
                     });
+                    ui.separator();
+                    let mut rasterizer_changed = false;
+                    ui.horizontal( |ui| {
+                        ui.label("Rasterizador:");
+                        egui::ComboBox::from_id_source("rasterizador")
+                            .selected_text(canvas.rasterizer.name())
+                            .show_ui(ui, |ui| {
+                                for &rasterizer in raster::rasterizers() {
+                                    let selected = rasterizer.name() == canvas.rasterizer.name();
+                                    if ui.selectable_label(selected, rasterizer.name()).clicked() && !selected {
+                                        canvas.rasterizer = rasterizer;
+                                        rasterizer_changed = true;
+                                    }
+                                }
+                            });
+                    });
+                    if ui.checkbox(&mut canvas.settings.antialiasing, "Antisserrilhamento").changed() {
+                        rasterizer_changed = true;
+                    }
+                    ui.horizontal( |ui| {
+                        ui.label("Linhas das arestas:");
+                        egui::ComboBox::from_id_source("linhas")
+                            .selected_text(canvas.settings.line_algorithm.name())
+                            .show_ui(ui, |ui| {
+                                for line_algorithm in raster::LineAlgorithm::ALL {
+                                    if ui.selectable_value(&mut canvas.settings.line_algorithm, line_algorithm, line_algorithm.name()).changed() {
+                                        rasterizer_changed = true;
+                                    }
+                                }
+                            });
+                    });
+                    ui.horizontal( |ui| {
+                        ui.label("Visibilidade:");
+                        if ui.radio_value(&mut canvas.settings.depth_test, false, "Algoritmo do pintor").changed() {
+                            rasterizer_changed = true;
+                        }
+                        if ui.radio_value(&mut canvas.settings.depth_test, true, "Z-buffer").changed() {
+                            rasterizer_changed = true;
+                        }
+                    });
+                    let mut lighting = canvas.settings.light.is_some();
+                    if ui.checkbox(&mut lighting, "Luz pontual").changed() {
+                        canvas.settings.light = if lighting {
+                            Some(raster::PointLight {
+                                position: [WIDTH / 2.0, HEIGHT / 2.0, 300.0],
+                                ..Default::default()
+                            })
+                        } else {
+                            None
+                        };
+                        rasterizer_changed = true;
+                    }
+                    if let Some(light) = canvas.settings.light.as_mut() {
+                        ui.horizontal( |ui| {
+                            ui.label("Posição:");
+                            rasterizer_changed |= ui.add(egui::DragValue::new(&mut light.position[0]).prefix("x: ")).changed();
+                            rasterizer_changed |= ui.add(egui::DragValue::new(&mut light.position[1]).prefix("y: ")).changed();
+                            rasterizer_changed |= ui.add(egui::DragValue::new(&mut light.position[2]).prefix("altura: ")).changed();
+                        });
+                        ui.horizontal( |ui| {
+                            ui.label("Cor:");
+                            rasterizer_changed |= ui.color_edit_button_srgb(&mut light.color).changed();
+                        });
+                        ui.horizontal( |ui| {
+                            ui.label("Ambiente:");
+                            rasterizer_changed |= ui.add(egui::DragValue::new(&mut light.ambient).speed(0.01).clamp_range(0.0..=1.0)).changed();
+                            ui.label("Especular:");
+                            rasterizer_changed |= ui.add(egui::DragValue::new(&mut light.specular).speed(0.01).clamp_range(0.0..=1.0)).changed();
+                            ui.label("Brilho:");
+                            rasterizer_changed |= ui.add(egui::DragValue::new(&mut light.shininess).clamp_range(1.0..=256.0)).changed();
+                        });
+                    }
+                    if rasterizer_changed {
+                        for (_, mut triangle) in triangles_query.iter_mut() {
+                            triangle.redraw = true;
+                        }
+                        for mut polygon in polygons_query.iter_mut() {
+                            polygon.redraw = true;
+                        }
+                    }
+                    if ui.add(egui::Button::new("Quad inclinado: afim × perspectiva")).clicked() {
+                        for triangle in tilted_quads(state.triangles_count) {
+                            commands.spawn(triangle);
+                            state.triangles_count += 1;
+                        }
+                    }
+                    ui.label(format!("Última pintura: {:.2} ms", canvas.render_time.as_secs_f64() * 1000.0));
+                    if ui.add(egui::Button::new("Comparar rasterizadores")).clicked() {
+                        let shapes = scene_shapes(&triangles_query, &polygons_query);
+                        state.rasterizer_comparison = compare_rasterizers(&canvas.settings, &shapes);
+                    }
+                    for line in state.rasterizer_comparison.iter() {
+                        ui.label(line);
+                    }
+                    ui.separator();
+                    ui.horizontal( |ui| {
+                        ui.label("Imagem:");
+                        ui.add(egui::TextEdit::singleline(&mut state.export_path));
+                    });
+                    ui.horizontal( |ui| {
+                        ui.label("Escala:");
+                        ui.add(egui::DragValue::new(&mut state.export_scale).clamp_range(1..=8));
+                        if ui.add(egui::Button::new("Exportar PNG")).clicked() {
+                            let shapes = scene_shapes(&triangles_query, &polygons_query);
+                            state.export_message = Some(match export_png(canvas.rasterizer, &canvas.settings, &shapes, state.export_scale, &state.export_path) {
+                                Ok(()) => format!("Imagem salva em {}.", state.export_path),
+                                Err(error) => format!("Erro ao exportar: {}", error),
+                            });
+                        }
+                    });
+                    if let Some(export_message) = state.export_message.clone() {
+                        ui.label(export_message);
+                    }
+                    ui.separator();
+                    ui.horizontal( |ui| {
+                        ui.label("Cena:");
+                        ui.add(egui::TextEdit::singleline(&mut state.scene_path));
+                    });
+                    ui.horizontal( |ui| {
+                        if ui.add(egui::Button::new("Salvar cena")).clicked() {
+                            state.scene_message = Some(match save_scene(
+                                &state.scene_path,
+                                canvas.rasterizer,
+                                &canvas.settings,
+                                state.triangles_count,
+                                &vertex_pool.vertices,
+                                triangles_query.iter().map(|(_, triangle)| triangle),
+                                polygons_query.iter(),
+                            ) {
+                                Ok(()) => format!("Cena salva em {}.", state.scene_path),
+                                Err(error) => format!("Erro ao salvar: {}", error),
+                            });
+                        }
+                        if ui.add(egui::Button::new("Carregar cena")).clicked() {
+                            match load_scene(&state.scene_path) {
+                                Ok(scene) => {
+                                    state.scene_message = Some(if scene.missing_textures.is_empty() {
+                                        format!("Cena carregada de {}.", state.scene_path)
+                                    } else {
+                                        format!("Cena carregada, sem as texturas: {}.", scene.missing_textures.join(", "))
+                                    });
+                                    // a cena atual é substituída pela carregada quando os
+                                    // comandos forem aplicados, com o pool e os triângulos juntos
+                                    commands.add(move |world: &mut World| scene.replace(world));
+                                }
+                                Err(error) => {
+                                    state.scene_message = Some(format!("Erro ao carregar: {}", error));
+                                }
+                            }
+                        }
+                    });
+                    if let Some(scene_message) = state.scene_message.clone() {
+                        ui.label(scene_message);
+                    }
                 }
                 Function::Create => {
                     ui.label("Clique com o botão esquerdo do mouse para adicionar pontos.");
                     ui.separator();
+                    if ui.checkbox(&mut state.draw_polygon, "Desenhar polígono").changed() && !state.draw_polygon && state.new_triangle.len() > 3 {
+                        // de volta ao triângulo: só os três primeiros pontos ficam
+                        state.new_triangle.truncate(3);
+                        state.spawn_vertex_selectors = true;
+                    }
+                    if state.draw_polygon {
+                        ui.label("Clique sobre o primeiro ponto (ou em Fechar) para fechar o polígono, que é cortado em triângulos.");
+                    }
+                    ui.separator();
                     ui.label("Use o seletor de cor abaixo para escolher a cor dos vértices.");
                     ui.horizontal( |ui| {
                         ui.label("Cor:");
-                        ui.color_edit_button_srgb(&mut state.vertex_color_picker);
+                        ui.color_edit_button_srgba_unmultiplied(&mut state.vertex_color_picker);
                     });
                     ui.separator();
                     ui.checkbox(&mut state.constant_edges, "Arestas com cor constante");
@@ -74,23 +279,123 @@ fn update_ui(
                         ui.color_edit_button_srgb(&mut state.edges_color_picker);
                     });
                     ui.separator();
-                    if ui.add(egui::Button::new("Voltar")).clicked() {
-                        for (entity, _) in vertex_selector_query.iter() {
-                            commands.entity(entity).despawn();
+                    ui.horizontal( |ui| {
+                        if state.draw_polygon && ui.add_enabled(state.new_triangle.len() >= 3, egui::Button::new("Fechar")).clicked() {
+                            close_drawn_polygon(&mut commands, &mut state, &mut vertex_pool);
                         }
-                        state.function = Function::None;
-                        state.new_triangle.clear();
+                        if ui.add(egui::Button::new("Voltar")).clicked() {
+                            for (entity, _) in vertex_selector_query.iter() {
+                                commands.entity(entity).despawn();
+                            }
+                            state.function = Function::None;
+                            state.new_triangle.clear();
+                            state.draw_polygon = false;
+                            state.triangulation_message = None;
+                        }
+                    });
+                    if let Some(triangulation_message) = state.triangulation_message.clone() {
+                        ui.label(triangulation_message);
+                    }
+                },
+                Function::CreatePolygon => {
+                    ui.label("Clique com o botão esquerdo do mouse para adicionar pontos. Clique sobre o primeiro ponto (ou em Fechar) para fechar o polígono.");
+                    ui.separator();
+                    ui.label("Use o seletor de cor abaixo para escolher a cor dos vértices.");
+                    ui.horizontal( |ui| {
+                        ui.label("Cor:");
+                        ui.color_edit_button_srgba_unmultiplied(&mut state.vertex_color_picker);
+                    });
+                    ui.separator();
+                    ui.checkbox(&mut state.constant_edges, "Arestas com cor constante");
+                    ui.horizontal( |ui| {
+                        ui.label("Cor:");
+                        ui.color_edit_button_srgb(&mut state.edges_color_picker);
+                    });
+                    ui.separator();
+                    ui.horizontal( |ui| {
+                        ui.label("Preenchimento:");
+                        for fill_rule in raster::FillRule::ALL {
+                            ui.radio_value(&mut state.fill_rule, fill_rule, fill_rule.name());
+                        }
+                    });
+                    ui.separator();
+                    ui.horizontal( |ui| {
+                        if ui.add_enabled(state.new_polygon.len() >= 3, egui::Button::new("Fechar")).clicked() {
+                            close_polygon(&mut commands, &mut state);
+                        }
+                        if ui.add(egui::Button::new("Voltar")).clicked() {
+                            for (entity, _) in vertex_selector_query.iter() {
+                                commands.entity(entity).despawn();
+                            }
+                            state.function = Function::None;
+                            state.new_polygon.clear();
+                        }
+                    });
+                },
+                Function::CreateDelaunay => {
+                    ui.label("Clique com o botão esquerdo do mouse para adicionar pontos, ou carregue-os de um arquivo com um ponto por linha (x y r g b [a]).");
+                    ui.separator();
+                    ui.label("Use o seletor de cor abaixo para escolher a cor dos pontos.");
+                    ui.horizontal( |ui| {
+                        ui.label("Cor:");
+                        ui.color_edit_button_srgba_unmultiplied(&mut state.vertex_color_picker);
+                    });
+                    ui.separator();
+                    ui.horizontal( |ui| {
+                        ui.label("Arquivo:");
+                        ui.add(egui::TextEdit::singleline(&mut state.points_path));
+                        if ui.button("Carregar").clicked() {
+                            match load_points(&state.points_path) {
+                                Ok(points) => {
+                                    state.point_cloud.extend(points);
+                                    state.points_message = None;
+                                    state.spawn_vertex_selectors = true;
+                                }
+                                Err(error) => {
+                                    state.points_message = Some(format!("Erro ao carregar: {}", error));
+                                }
+                            }
+                        }
+                    });
+                    ui.separator();
+                    ui.checkbox(&mut state.constant_edges, "Arestas com cor constante");
+                    ui.horizontal( |ui| {
+                        ui.label("Cor:");
+                        ui.color_edit_button_srgb(&mut state.edges_color_picker);
+                    });
+                    ui.separator();
+                    ui.label(format!("{} pontos.", state.point_cloud.len()));
+                    ui.horizontal( |ui| {
+                        if ui.add_enabled(state.point_cloud.len() >= 3, egui::Button::new("Triangular")).clicked() {
+                            triangulate_point_cloud(&mut commands, &mut state, &mut vertex_pool);
+                        }
+                        if ui.add_enabled(!state.point_cloud.is_empty(), egui::Button::new("Limpar")).clicked() {
+                            state.point_cloud.clear();
+                            state.spawn_vertex_selectors = true;
+                        }
+                        if ui.add(egui::Button::new("Voltar")).clicked() {
+                            for (entity, _) in vertex_selector_query.iter() {
+                                commands.entity(entity).despawn();
+                            }
+                            state.function = Function::None;
+                            state.point_cloud.clear();
+                            state.points_message = None;
+                        }
+                    });
+                    if let Some(points_message) = state.points_message.clone() {
+                        ui.label(points_message);
                     }
                 },
                 Function::Select => {
-                    ui.label("Selecione um triângulo.");
+                    ui.label("Selecione um triângulo ou polígono.");
                     ui.separator();
                     if ui.add(egui::Button::new("Voltar")).clicked() {
                         state.function = Function::None;
                     }
                 }
                 Function::Modify(entity) => {
-                    if let Ok(mut triangle) = triangles_query.get_mut(entity) {
+                    let mut welding = None;
+                    if let Ok((_, mut triangle)) = triangles_query.get_mut(entity) {
                         ui.label(format!("Você está editando o triângulo {}.", triangle.index));
 
                         ui.separator();
@@ -102,7 +407,7 @@ fn update_ui(
                         ui.label("Para atribuir a cor abaixo, clique com o botão direito do mouse sobre um seletor.");
                         ui.horizontal( |ui| {
                             ui.label("Cor:");
-                            ui.color_edit_button_srgb(&mut state.vertex_color_picker);
+                            ui.color_edit_button_srgba_unmultiplied(&mut state.vertex_color_picker);
                         });
 
                         ui.separator();
@@ -128,6 +433,48 @@ fn update_ui(
 
                         ui.separator();
 
+                        ui.horizontal( |ui| {
+                            ui.label("Sombreamento:");
+                            egui::ComboBox::from_id_source("sombreamento")
+                                .selected_text(triangle.shading.name())
+                                .show_ui(ui, |ui| {
+                                    for shading in raster::ShadingModel::ALL {
+                                        if ui.selectable_value(&mut triangle.shading, shading, shading.name()).changed() {
+                                            triangle.redraw = true;
+                                        }
+                                    }
+                                });
+                        });
+                        ui.horizontal( |ui| {
+                            ui.label("Shader:");
+                            egui::ComboBox::from_id_source("shader")
+                                .selected_text(triangle.shader.name())
+                                .show_ui(ui, |ui| {
+                                    for shader in raster::shaders() {
+                                        let selected = shader.name() == triangle.shader.name();
+                                        if ui.selectable_label(selected, shader.name()).clicked() && !selected {
+                                            triangle.shader = shader;
+                                            triangle.redraw = true;
+                                        }
+                                    }
+                                });
+                        });
+
+                        ui.separator();
+
+                        // vértices soldados acompanham as edições dos outros triângulos
+                        ui.label(format!("Vértices soldados: {} de 3.", triangle.shared.iter().flatten().count()));
+                        ui.horizontal( |ui| {
+                            if ui.add(egui::Button::new("Soldar vértices próximos")).clicked() {
+                                welding = Some(true);
+                            }
+                            if ui.add_enabled(triangle.shared.iter().any(Option::is_some), egui::Button::new("Desoldar")).clicked() {
+                                welding = Some(false);
+                            }
+                        });
+
+                        ui.separator();
+
                         ui.checkbox(&mut state.show_properties_window, "Exibir janela de propriedades");
 
                         ui.separator();
@@ -145,15 +492,135 @@ fn update_ui(
                                 for (entity, _) in vertex_selector_query.iter() {
                                     commands.entity(entity).despawn();
                                 }
-                                // despawna sprite do triângulo
-                                if let Ok(triangle_sprite) = triangle_sprites_query.get(entity) {
-                                    if let Some(entity) = triangle_sprite.0 {
-                                        commands.entity(entity).despawn();
+                                // despawna o triângulo (a tela é repintada sem ele)
+                                commands.entity(entity).despawn();
+                                state.function = Function::None;
+                            }
+                        });
+                    }
+                    match welding {
+                        Some(true) => weld(&mut vertex_pool, &mut triangles_query, entity),
+                        Some(false) => unweld(&mut triangles_query, entity),
+                        None => {}
+                    }
+                },
+                Function::ModifyPolygon(entity) => {
+                    if let Ok(mut polygon) = polygons_query.get_mut(entity) {
+                        ui.label(format!("Você está editando o polígono {} ({} vértices, {} furos).", polygon.index, polygon.vertices.len(), polygon.holes.len()));
+
+                        ui.separator();
+
+                        ui.label("Para mover um vértice, clique com o botão esquerdo do mouse sobre um seletor para selecioná-lo. Depois, clique na nova posição.");
+
+                        ui.separator();
+
+                        ui.label("Para atribuir a cor abaixo, clique com o botão direito do mouse sobre um seletor.");
+                        ui.horizontal( |ui| {
+                            ui.label("Cor:");
+                            ui.color_edit_button_srgba_unmultiplied(&mut state.vertex_color_picker);
+                        });
+
+                        ui.separator();
+
+                        let mut edges_color_changed = false;
+                        let constant_edges_changed = ui.checkbox(&mut state.constant_edges, "Arestas com cor constante").changed();
+                        ui.horizontal( |ui| {
+                            ui.label("Cor:");
+                            edges_color_changed = ui.color_edit_button_srgb(&mut state.edges_color_picker).changed();
+                        });
+                        if constant_edges_changed || edges_color_changed {
+                            polygon.edges_color = if state.constant_edges {
+                                Some(state.edges_color_picker)
+                            } else {
+                                None
+                            };
+                            polygon.redraw = true;
+                        }
+
+                        ui.separator();
+
+                        ui.horizontal( |ui| {
+                            ui.label("Preenchimento:");
+                            for fill_rule in raster::FillRule::ALL {
+                                if ui.radio_value(&mut polygon.fill_rule, fill_rule, fill_rule.name()).changed() {
+                                    state.fill_rule = fill_rule;
+                                    polygon.redraw = true;
+                                }
+                            }
+                        });
+                        ui.horizontal( |ui| {
+                            ui.label("Mistura:");
+                            egui::ComboBox::from_id_source("mistura_poligono")
+                                .selected_text(polygon.blend_mode.name())
+                                .show_ui(ui, |ui| {
+                                    for mode in raster::BlendMode::ALL {
+                                        if ui.selectable_value(&mut polygon.blend_mode, mode, mode.name()).changed() {
+                                            polygon.redraw = true;
+                                        }
                                     }
+                                });
+                        });
+
+                        ui.separator();
+
+                        ui.horizontal( |ui| {
+                            if ui.add(egui::Button::new("Adicionar furo")).clicked() {
+                                state.new_polygon.clear();
+                                state.selected_vertex = None;
+                                state.function = Function::CreateHole(entity);
+                                state.spawn_vertex_selectors = true;
+                            }
+                            if ui.add_enabled(!polygon.holes.is_empty(), egui::Button::new("Remover furos")).clicked() {
+                                polygon.holes.clear();
+                                polygon.redraw = true;
+                                state.selected_vertex = None;
+                                state.spawn_vertex_selectors = true;
+                            }
+                        });
+
+                        ui.separator();
+
+                        ui.horizontal( |ui| {
+                            if ui.add(egui::Button::new("Voltar")).clicked() {
+                                for (entity, _) in vertex_selector_query.iter() {
+                                    commands.entity(entity).despawn();
                                 }
-                                // despawna sprite do triângulo
+                                state.function = Function::None;
+                                state.selected_vertex = None;
+                                state.constant_edges = false;
+                            }
+                            if ui.add(egui::Button::new("Deletar")).clicked() {
+                                for (entity, _) in vertex_selector_query.iter() {
+                                    commands.entity(entity).despawn();
+                                }
+                                // despawna o polígono (a tela é repintada sem ele)
                                 commands.entity(entity).despawn();
                                 state.function = Function::None;
+                                state.selected_vertex = None;
+                            }
+                        });
+                    }
+                },
+                Function::CreateHole(entity) => {
+                    if let Ok(mut polygon) = polygons_query.get_mut(entity) {
+                        ui.label(format!("Você está adicionando um furo ao polígono {}.", polygon.index));
+                        ui.separator();
+                        ui.label("Clique com o botão esquerdo do mouse para adicionar pontos dentro do polígono. Clique sobre o primeiro ponto (ou em Fechar) para fechar o furo.");
+                        ui.separator();
+                        ui.label("Use o seletor de cor abaixo para escolher a cor dos vértices.");
+                        ui.horizontal( |ui| {
+                            ui.label("Cor:");
+                            ui.color_edit_button_srgba_unmultiplied(&mut state.vertex_color_picker);
+                        });
+                        ui.separator();
+                        ui.horizontal( |ui| {
+                            if ui.add_enabled(state.new_polygon.len() >= 3, egui::Button::new("Fechar")).clicked() {
+                                close_hole(&mut state, entity, &mut polygon);
+                            }
+                            if ui.add(egui::Button::new("Voltar")).clicked() {
+                                state.new_polygon.clear();
+                                state.function = Function::ModifyPolygon(entity);
+                                state.spawn_vertex_selectors = true;
                             }
                         });
                     }
@@ -163,11 +630,148 @@ fn update_ui(
     
     if state.show_properties_window {
         if let Function::Modify(entity) = state.function {
-            if let Ok(mut triangle) = triangles_query.get_mut(entity) {
+            if let Ok((_, mut triangle)) = triangles_query.get_mut(entity) {
                 egui::Window::new("Propriedades")
                     .fixed_size([150.0, 200.0])
                     .show(contexts.ctx_mut(), |ui| {
                         ui.vertical(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("Mistura:");
+                                egui::ComboBox::from_id_source("mistura")
+                                    .selected_text(triangle.blend_mode.name())
+                                    .show_ui(ui, |ui| {
+                                        for mode in raster::BlendMode::ALL {
+                                            if ui.selectable_value(&mut triangle.blend_mode, mode, mode.name()).changed() {
+                                                triangle.redraw = true;
+                                            }
+                                        }
+                                    });
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Interpolação:");
+                                egui::ComboBox::from_id_source("espaco_de_cor")
+                                    .selected_text(triangle.color_space.name())
+                                    .show_ui(ui, |ui| {
+                                        for color_space in raster::ColorSpace::ALL {
+                                            if ui.selectable_value(&mut triangle.color_space, color_space, color_space.name()).changed() {
+                                                triangle.redraw = true;
+                                            }
+                                        }
+                                    });
+                            });
+
+                            if ui.checkbox(&mut triangle.perspective_correct, "Correção de perspectiva").changed() {
+                                triangle.redraw = true;
+                            }
+
+                            ui.separator();
+
+                            ui.label("Textura:");
+                            ui.horizontal(|ui| {
+                                ui.label("Arquivo:");
+                                ui.add(egui::TextEdit::singleline(&mut state.texture_path));
+                            });
+                            ui.horizontal(|ui| {
+                                if ui.button("Carregar").clicked() {
+                                    match load_texture(&state.texture_path) {
+                                        Ok(texture) => {
+                                            let texture = Arc::new(texture);
+                                            triangle.texture = Some(match triangle.texture.take() {
+                                                Some(mapping) => raster::TextureMapping { texture, ..mapping },
+                                                None => raster::TextureMapping::new(texture),
+                                            });
+                                            triangle.redraw = true;
+                                            state.texture_message = None;
+                                        }
+                                        Err(error) => {
+                                            state.texture_message = Some(format!("Erro ao carregar: {}", error));
+                                        }
+                                    }
+                                }
+                                if triangle.texture.is_some() && ui.button("Remover").clicked() {
+                                    triangle.texture = None;
+                                    triangle.redraw = true;
+                                }
+                            });
+                            if let Some(texture_message) = state.texture_message.clone() {
+                                ui.label(texture_message);
+                            }
+                            let mut texture_changed = false;
+                            if let Some(mapping) = triangle.texture.as_mut() {
+                                ui.label(format!(
+                                    "{} ({}x{})",
+                                    mapping.texture.name,
+                                    mapping.texture.width,
+                                    mapping.texture.height,
+                                ));
+                                ui.horizontal(|ui| {
+                                    ui.label("Filtro:");
+                                    egui::ComboBox::from_id_source("filtro")
+                                        .selected_text(mapping.filter.name())
+                                        .show_ui(ui, |ui| {
+                                            for filter in raster::TextureFilter::ALL {
+                                                if ui.selectable_value(&mut mapping.filter, filter, filter.name()).changed() {
+                                                    texture_changed = true;
+                                                }
+                                            }
+                                        });
+                                });
+                                if ui.checkbox(&mut mapping.modulate, "Modular pela cor dos vértices").changed() {
+                                    texture_changed = true;
+                                }
+                            }
+                            if texture_changed {
+                                triangle.redraw = true;
+                            }
+
+                            ui.separator();
+
+                            ui.label("Atributos:");
+                            let names: Vec<String> = triangle.first.attributes.iter().map(|attribute| attribute.name.clone()).collect();
+                            let mut attributes_changed = false;
+                            let mut removed_attribute = None;
+                            for name in &names {
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("{}:", name));
+                                    let triangle = &mut *triangle;
+                                    for vertex in [&mut triangle.first, &mut triangle.middle, &mut triangle.last] {
+                                        if let Some(attribute) = vertex.attributes.iter_mut().find(|attribute| &attribute.name == name) {
+                                            if ui.add(egui::DragValue::new(&mut attribute.value).speed(0.01)).changed() {
+                                                attributes_changed = true;
+                                            }
+                                        }
+                                    }
+                                    if ui.button("Remover").clicked() {
+                                        removed_attribute = Some(name.clone());
+                                    }
+                                });
+                            }
+                            ui.horizontal(|ui| {
+                                ui.add(egui::TextEdit::singleline(&mut state.attribute_name_string).desired_width(90.0));
+                                let name = state.attribute_name_string.trim().to_string();
+                                if ui.button("Adicionar").clicked() && !name.is_empty() {
+                                    let triangle = &mut *triangle;
+                                    for vertex in [&mut triangle.first, &mut triangle.middle, &mut triangle.last] {
+                                        if vertex.attribute(&name).is_none() {
+                                            vertex.attributes.push(raster::Attribute::new(name.clone(), 0.0));
+                                        }
+                                    }
+                                    attributes_changed = true;
+                                }
+                            });
+                            if let Some(name) = removed_attribute {
+                                let triangle = &mut *triangle;
+                                for vertex in [&mut triangle.first, &mut triangle.middle, &mut triangle.last] {
+                                    vertex.attributes.retain(|attribute| attribute.name != name);
+                                }
+                                attributes_changed = true;
+                            }
+                            if attributes_changed {
+                                triangle.redraw = true;
+                            }
+
+                            ui.separator();
+
                             if let Some(edges_color) = triangle.edges_color {
                                 ui.label("Cor das arestas:");
                                 ui.horizontal(|ui| {
@@ -224,6 +828,34 @@ fn update_ui(
                                 ui.label("Y:");
                                 ui.add(egui::TextEdit::singleline(&mut state.first_position_y_string));
                             });
+                            ui.horizontal(|ui| {
+                                ui.label("Z:");
+                                ui.add(egui::TextEdit::singleline(&mut state.first_depth_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("U:");
+                                ui.add(egui::TextEdit::singleline(&mut state.first_uv_u_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("V:");
+                                ui.add(egui::TextEdit::singleline(&mut state.first_uv_v_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("W:");
+                                ui.add(egui::TextEdit::singleline(&mut state.first_w_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("NX:");
+                                ui.add(egui::TextEdit::singleline(&mut state.first_normal_x_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("NY:");
+                                ui.add(egui::TextEdit::singleline(&mut state.first_normal_y_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("NZ:");
+                                ui.add(egui::TextEdit::singleline(&mut state.first_normal_z_string));
+                            });
                             ui.horizontal(|ui| {
                                 ui.label("R:");
                                 ui.add(egui::TextEdit::singleline(&mut state.first_color_r_string));
@@ -236,43 +868,54 @@ fn update_ui(
                                 ui.label("B:");
                                 ui.add(egui::TextEdit::singleline(&mut state.first_color_b_string));
                             });
+                            ui.horizontal(|ui| {
+                                ui.label("A:");
+                                ui.add(egui::TextEdit::singleline(&mut state.first_color_a_string));
+                            });
                             if state.first_vertex_string_parsing_error {
                                 ui.label("Algo aqui não está certo!");
                             }
                             ui.horizontal(|ui| {
                                 if ui.button("Aplicar").clicked() {
                                     if let (
-                                        Ok(mut xp),
-                                        Ok(mut yp),
+                                        Ok(xp),
+                                        Ok(yp),
+                                        Ok(zp),
+                                        Ok(up),
+                                        Ok(vp),
+                                        Ok(wp),
+                                        Ok(nx),
+                                        Ok(ny),
+                                        Ok(nz),
                                         Ok(rc),
                                         Ok(gc),
                                         Ok(bc),
+                                        Ok(ac),
                                     ) = (
                                         state.first_position_x_string.parse::<f32>(),
                                         state.first_position_y_string.parse::<f32>(),
+                                        state.first_depth_string.parse::<f32>(),
+                                        state.first_uv_u_string.parse::<f32>(),
+                                        state.first_uv_v_string.parse::<f32>(),
+                                        state.first_w_string.parse::<f32>(),
+                                        state.first_normal_x_string.parse::<f32>(),
+                                        state.first_normal_y_string.parse::<f32>(),
+                                        state.first_normal_z_string.parse::<f32>(),
                                         state.first_color_r_string.parse::<u8>(),
                                         state.first_color_g_string.parse::<u8>(),
                                         state.first_color_b_string.parse::<u8>(),
+                                        state.first_color_a_string.parse::<u8>(),
                                     ) {
-                                        if xp >= WIDTH {
-                                            xp = WIDTH - 1.0;
-                                            state.first_position_x_string = xp.to_string();
-                                        } else if xp < 0.0 {
-                                            xp = 0.0;
-                                            state.first_position_x_string = xp.to_string();
-                                        }
-                                        if yp >= HEIGHT {
-                                            yp = HEIGHT - 1.0;
-                                            state.first_position_y_string = yp.to_string();
-                                        } else if yp < 0.0 {
-                                            yp = 0.0;
-                                            state.first_position_y_string = yp.to_string();
-                                        }
                                         triangle.first.position[0] = xp;
                                         triangle.first.position[1] = yp;
+                                        triangle.first.depth = zp;
+                                        triangle.first.uv = [up, vp];
+                                        triangle.first.w = wp;
+                                        triangle.first.normal = [nx, ny, nz];
                                         triangle.first.color[0] = rc;
                                         triangle.first.color[1] = gc;
                                         triangle.first.color[2] = bc;
+                                        triangle.first.color[3] = ac;
                                         triangle.redraw = true;
                                         state.spawn_vertex_selectors = true;
                                         state.first_vertex_string_parsing_error = false;
@@ -283,9 +926,17 @@ fn update_ui(
                                 if ui.button("Restaurar").clicked() {
                                     state.first_position_x_string = triangle.first.position[0].to_string();
                                     state.first_position_y_string = triangle.first.position[1].to_string();
+                                    state.first_depth_string = triangle.first.depth.to_string();
+                                    state.first_uv_u_string = triangle.first.uv[0].to_string();
+                                    state.first_uv_v_string = triangle.first.uv[1].to_string();
+                                    state.first_w_string = triangle.first.w.to_string();
+                                    state.first_normal_x_string = triangle.first.normal[0].to_string();
+                                    state.first_normal_y_string = triangle.first.normal[1].to_string();
+                                    state.first_normal_z_string = triangle.first.normal[2].to_string();
                                     state.first_color_r_string = triangle.first.color[0].to_string();
                                     state.first_color_g_string = triangle.first.color[1].to_string();
                                     state.first_color_b_string = triangle.first.color[2].to_string();
+                                    state.first_color_a_string = triangle.first.color[3].to_string();
                                 }
                             });
 
@@ -300,6 +951,34 @@ fn update_ui(
                                 ui.label("Y:");
                                 ui.add(egui::TextEdit::singleline(&mut state.middle_position_y_string));
                             });
+                            ui.horizontal(|ui| {
+                                ui.label("Z:");
+                                ui.add(egui::TextEdit::singleline(&mut state.middle_depth_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("U:");
+                                ui.add(egui::TextEdit::singleline(&mut state.middle_uv_u_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("V:");
+                                ui.add(egui::TextEdit::singleline(&mut state.middle_uv_v_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("W:");
+                                ui.add(egui::TextEdit::singleline(&mut state.middle_w_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("NX:");
+                                ui.add(egui::TextEdit::singleline(&mut state.middle_normal_x_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("NY:");
+                                ui.add(egui::TextEdit::singleline(&mut state.middle_normal_y_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("NZ:");
+                                ui.add(egui::TextEdit::singleline(&mut state.middle_normal_z_string));
+                            });
                             ui.horizontal(|ui| {
                                 ui.label("R:");
                                 ui.add(egui::TextEdit::singleline(&mut state.middle_color_r_string));
@@ -312,43 +991,54 @@ fn update_ui(
                                 ui.label("B:");
                                 ui.add(egui::TextEdit::singleline(&mut state.middle_color_b_string));
                             });
+                            ui.horizontal(|ui| {
+                                ui.label("A:");
+                                ui.add(egui::TextEdit::singleline(&mut state.middle_color_a_string));
+                            });
                             if state.middle_vertex_string_parsing_error {
                                 ui.label("Algo aqui não está certo!");
                             }
                             ui.horizontal(|ui| {
                                 if ui.button("Aplicar").clicked() {
                                     if let (
-                                        Ok(mut xp),
-                                        Ok(mut yp),
+                                        Ok(xp),
+                                        Ok(yp),
+                                        Ok(zp),
+                                        Ok(up),
+                                        Ok(vp),
+                                        Ok(wp),
+                                        Ok(nx),
+                                        Ok(ny),
+                                        Ok(nz),
                                         Ok(rc),
                                         Ok(gc),
                                         Ok(bc),
+                                        Ok(ac),
                                     ) = (
                                         state.middle_position_x_string.parse::<f32>(),
                                         state.middle_position_y_string.parse::<f32>(),
+                                        state.middle_depth_string.parse::<f32>(),
+                                        state.middle_uv_u_string.parse::<f32>(),
+                                        state.middle_uv_v_string.parse::<f32>(),
+                                        state.middle_w_string.parse::<f32>(),
+                                        state.middle_normal_x_string.parse::<f32>(),
+                                        state.middle_normal_y_string.parse::<f32>(),
+                                        state.middle_normal_z_string.parse::<f32>(),
                                         state.middle_color_r_string.parse::<u8>(),
                                         state.middle_color_g_string.parse::<u8>(),
                                         state.middle_color_b_string.parse::<u8>(),
+                                        state.middle_color_a_string.parse::<u8>(),
                                     ) {
-                                        if xp >= WIDTH {
-                                            xp = WIDTH - 1.0;
-                                            state.middle_position_x_string = xp.to_string();
-                                        } else if xp < 0.0 {
-                                            xp = 0.0;
-                                            state.middle_position_x_string = xp.to_string();
-                                        }
-                                        if yp >= HEIGHT {
-                                            yp = HEIGHT - 1.0;
-                                            state.middle_position_y_string = yp.to_string();
-                                        } else if yp < 0.0 {
-                                            yp = 0.0;
-                                            state.middle_position_y_string = yp.to_string();
-                                        }
                                         triangle.middle.position[0] = xp;
                                         triangle.middle.position[1] = yp;
+                                        triangle.middle.depth = zp;
+                                        triangle.middle.uv = [up, vp];
+                                        triangle.middle.w = wp;
+                                        triangle.middle.normal = [nx, ny, nz];
                                         triangle.middle.color[0] = rc;
                                         triangle.middle.color[1] = gc;
                                         triangle.middle.color[2] = bc;
+                                        triangle.middle.color[3] = ac;
                                         triangle.redraw = true;
                                         state.spawn_vertex_selectors = true;
                                         state.middle_vertex_string_parsing_error = false;
@@ -359,9 +1049,17 @@ fn update_ui(
                                 if ui.button("Restaurar").clicked() {
                                     state.middle_position_x_string = triangle.middle.position[0].to_string();
                                     state.middle_position_y_string = triangle.middle.position[1].to_string();
+                                    state.middle_depth_string = triangle.middle.depth.to_string();
+                                    state.middle_uv_u_string = triangle.middle.uv[0].to_string();
+                                    state.middle_uv_v_string = triangle.middle.uv[1].to_string();
+                                    state.middle_w_string = triangle.middle.w.to_string();
+                                    state.middle_normal_x_string = triangle.middle.normal[0].to_string();
+                                    state.middle_normal_y_string = triangle.middle.normal[1].to_string();
+                                    state.middle_normal_z_string = triangle.middle.normal[2].to_string();
                                     state.middle_color_r_string = triangle.middle.color[0].to_string();
                                     state.middle_color_g_string = triangle.middle.color[1].to_string();
                                     state.middle_color_b_string = triangle.middle.color[2].to_string();
+                                    state.middle_color_a_string = triangle.middle.color[3].to_string();
                                 }
                             });
 
@@ -376,6 +1074,34 @@ fn update_ui(
                                 ui.label("Y:");
                                 ui.add(egui::TextEdit::singleline(&mut state.last_position_y_string));
                             });
+                            ui.horizontal(|ui| {
+                                ui.label("Z:");
+                                ui.add(egui::TextEdit::singleline(&mut state.last_depth_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("U:");
+                                ui.add(egui::TextEdit::singleline(&mut state.last_uv_u_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("V:");
+                                ui.add(egui::TextEdit::singleline(&mut state.last_uv_v_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("W:");
+                                ui.add(egui::TextEdit::singleline(&mut state.last_w_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("NX:");
+                                ui.add(egui::TextEdit::singleline(&mut state.last_normal_x_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("NY:");
+                                ui.add(egui::TextEdit::singleline(&mut state.last_normal_y_string));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("NZ:");
+                                ui.add(egui::TextEdit::singleline(&mut state.last_normal_z_string));
+                            });
                             ui.horizontal(|ui| {
                                 ui.label("R:");
                                 ui.add(egui::TextEdit::singleline(&mut state.last_color_r_string));
@@ -388,43 +1114,54 @@ fn update_ui(
                                 ui.label("B:");
                                 ui.add(egui::TextEdit::singleline(&mut state.last_color_b_string));
                             });
+                            ui.horizontal(|ui| {
+                                ui.label("A:");
+                                ui.add(egui::TextEdit::singleline(&mut state.last_color_a_string));
+                            });
                             if state.last_vertex_string_parsing_error {
                                 ui.label("Algo aqui não está certo!");
                             }
                             ui.horizontal(|ui| {
                                 if ui.button("Aplicar").clicked() {
                                     if let (
-                                        Ok(mut xp),
-                                        Ok(mut yp),
+                                        Ok(xp),
+                                        Ok(yp),
+                                        Ok(zp),
+                                        Ok(up),
+                                        Ok(vp),
+                                        Ok(wp),
+                                        Ok(nx),
+                                        Ok(ny),
+                                        Ok(nz),
                                         Ok(rc),
                                         Ok(gc),
                                         Ok(bc),
+                                        Ok(ac),
                                     ) = (
                                         state.last_position_x_string.parse::<f32>(),
                                         state.last_position_y_string.parse::<f32>(),
+                                        state.last_depth_string.parse::<f32>(),
+                                        state.last_uv_u_string.parse::<f32>(),
+                                        state.last_uv_v_string.parse::<f32>(),
+                                        state.last_w_string.parse::<f32>(),
+                                        state.last_normal_x_string.parse::<f32>(),
+                                        state.last_normal_y_string.parse::<f32>(),
+                                        state.last_normal_z_string.parse::<f32>(),
                                         state.last_color_r_string.parse::<u8>(),
                                         state.last_color_g_string.parse::<u8>(),
                                         state.last_color_b_string.parse::<u8>(),
+                                        state.last_color_a_string.parse::<u8>(),
                                     ) {
-                                        if xp >= WIDTH {
-                                            xp = WIDTH - 1.0;
-                                            state.last_position_x_string = xp.to_string();
-                                        } else if xp < 0.0 {
-                                            xp = 0.0;
-                                            state.last_position_x_string = xp.to_string();
-                                        }
-                                        if yp >= HEIGHT {
-                                            yp = HEIGHT - 1.0;
-                                            state.last_position_y_string = yp.to_string();
-                                        } else if yp < 0.0 {
-                                            yp = 0.0;
-                                            state.last_position_y_string = yp.to_string();
-                                        }
                                         triangle.last.position[0] = xp;
                                         triangle.last.position[1] = yp;
+                                        triangle.last.depth = zp;
+                                        triangle.last.uv = [up, vp];
+                                        triangle.last.w = wp;
+                                        triangle.last.normal = [nx, ny, nz];
                                         triangle.last.color[0] = rc;
                                         triangle.last.color[1] = gc;
                                         triangle.last.color[2] = bc;
+                                        triangle.last.color[3] = ac;
                                         triangle.redraw = true;
                                         state.spawn_vertex_selectors = true;
                                         state.last_vertex_string_parsing_error = false;
@@ -435,9 +1172,17 @@ fn update_ui(
                                 if ui.button("Restaurar").clicked() {
                                     state.last_position_x_string = triangle.last.position[0].to_string();
                                     state.last_position_y_string = triangle.last.position[1].to_string();
+                                    state.last_depth_string = triangle.last.depth.to_string();
+                                    state.last_uv_u_string = triangle.last.uv[0].to_string();
+                                    state.last_uv_v_string = triangle.last.uv[1].to_string();
+                                    state.last_w_string = triangle.last.w.to_string();
+                                    state.last_normal_x_string = triangle.last.normal[0].to_string();
+                                    state.last_normal_y_string = triangle.last.normal[1].to_string();
+                                    state.last_normal_z_string = triangle.last.normal[2].to_string();
                                     state.last_color_r_string = triangle.last.color[0].to_string();
                                     state.last_color_g_string = triangle.last.color[1].to_string();
                                     state.last_color_b_string = triangle.last.color[2].to_string();
+                                    state.last_color_a_string = triangle.last.color[3].to_string();
                                 }
                             });
                         });
@@ -448,12 +1193,29 @@ fn update_ui(
 }
 
 
+// --------------------
+// triângulos e polígonos da cena, na ordem de pintura
+// --------------------
+
+fn scene_shapes(triangles_query: &Query<(Entity, &mut Triangle)>, polygons_query: &Query<&mut Polygon>) -> Vec<raster::Shape> {
+    let mut shapes: Vec<(usize, raster::Shape)> = triangles_query
+        .iter()
+        .map(|(_, triangle)| (triangle.index, raster::Shape::Triangle(triangle.to_raster())))
+        .chain(polygons_query.iter().map(|polygon| (polygon.index, raster::Shape::Polygon(polygon.to_raster()))))
+        .collect();
+    shapes.sort_by_key(|(index, _)| *index);
+
+    shapes.into_iter().map(|(_, shape)| shape).collect()
+}
+
+
 fn spawn_vertex_selectors(
     mut commands: Commands,
     mut state: ResMut<State>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     triangles_query: Query<&Triangle>,
+    polygons_query: Query<&Polygon>,
     vertex_selector_query: Query<Entity, With<VertexSelector>>,
 ) {
     if state.spawn_vertex_selectors {
@@ -463,17 +1225,14 @@ fn spawn_vertex_selectors(
 
         match state.function {
             Function::Create => {
-                let mut order: VertexOrder = VertexOrder::First;
-                let mut count: u8 = 0;
                 let mut z: f32 = 100.0;
 
-                for vertex in state.new_triangle.clone() {
-                    if count == 1 {
-                        order = VertexOrder::Middle;
-                    } else if count == 1 {
-                        order = VertexOrder::Last;
-                    }
-                    count += 1;
+                for (count, vertex) in state.new_triangle.iter().enumerate() {
+                    let order = match count {
+                        0 => VertexOrder::First,
+                        1 => VertexOrder::Middle,
+                        _ => VertexOrder::Last,
+                    };
 
                     commands.spawn((
                         VertexSelector(order.clone()),
@@ -724,9 +1483,88 @@ fn spawn_vertex_selectors(
             }
 
 
+            Function::CreatePolygon | Function::CreateHole(_) => {
+                let mut z: f32 = 100.0;
+
+                for (index, vertex) in state.new_polygon.iter().enumerate() {
+                    spawn_selector(&mut commands, &mut meshes, &mut materials, VertexOrder::Index(index), vertex, false, z);
+                    z += 3.0;
+                }
+            }
+
+
+            Function::CreateDelaunay => {
+                let mut z: f32 = 100.0;
+
+                for (index, vertex) in state.point_cloud.iter().enumerate() {
+                    spawn_selector(&mut commands, &mut meshes, &mut materials, VertexOrder::Index(index), vertex, false, z);
+                    z += 3.0;
+                }
+            }
+
+
+            Function::ModifyPolygon(entity) => {
+                if let Ok(polygon) = polygons_query.get(entity) {
+                    let mut z: f32 = 100.0;
+
+                    for (index, vertex) in polygon.all_vertices().enumerate() {
+                        let selected = state.selected_vertex == Some(VertexOrder::Index(index));
+                        spawn_selector(&mut commands, &mut meshes, &mut materials, VertexOrder::Index(index), vertex, selected, z);
+                        z += 3.0;
+                    }
+                }
+            }
+
+
             _ => {}
         }
 
         state.spawn_vertex_selectors = false;
     }
+}
+
+
+// --------------------
+// seletor de um vértice: círculos preto e branco em volta da cor do
+// vértice, que encolhe quando o vértice está selecionado
+// --------------------
+
+fn spawn_selector(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    order: VertexOrder,
+    vertex: &Vertex,
+    selected: bool,
+    z: f32,
+) {
+    let circles = [
+        (9.0, Color::BLACK),
+        (8.0, Color::WHITE),
+        (
+            if selected { 4.0 } else { 7.0 },
+            Color::Rgba {
+                red: vertex.color[0] as f32 / 255.0,
+                green: vertex.color[1] as f32 / 255.0,
+                blue: vertex.color[2] as f32 / 255.0,
+                alpha: 1.0,
+            },
+        ),
+    ];
+
+    for (layer, (radius, color)) in circles.into_iter().enumerate() {
+        commands.spawn((
+            VertexSelector(order.clone()),
+            MaterialMesh2dBundle {
+                mesh: meshes.add(shape::Circle::new(radius).into()).into(),
+                material: materials.add(ColorMaterial::from(color)),
+                transform: Transform::from_translation(Vec3::new(
+                    vertex.position[0],
+                    vertex.position[1],
+                    z + layer as f32,
+                )),
+                ..default()
+            },
+        ));
+    }
 }
\ No newline at end of file